@@ -10,6 +10,11 @@ mod plugins;
 mod settings;
 mod themes;
 mod topics;
+mod upstream;
+mod webhook;
 
+pub use backup::BackupChunk;
 pub use client::{DiscourseClient, VersionInfo};
 pub use models::*;
+pub use upstream::UpstreamClient;
+pub use webhook::post_webhook;
@@ -1,8 +1,42 @@
 use super::client::DiscourseClient;
 use super::error::http_error;
 use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use reqwest::blocking::Response;
+use reqwest::blocking::multipart::{Form, Part};
+use reqwest::header::RANGE;
 use serde_json::Value;
 
+/// One chunk of a resumable backup upload.
+///
+/// Mirrors the `resumable*` parameters the Discourse admin uploader sends;
+/// chunk numbers start at 1.
+#[derive(Debug, Clone)]
+pub struct BackupChunk<'a> {
+    pub filename: &'a str,
+    pub identifier: &'a str,
+    pub total_size: u64,
+    pub chunk_size: u64,
+    pub chunk_number: u64,
+    pub current_chunk_size: u64,
+}
+
+impl BackupChunk<'_> {
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("resumableFilename", self.filename.to_string()),
+            ("resumableIdentifier", self.identifier.to_string()),
+            ("resumableTotalSize", self.total_size.to_string()),
+            ("resumableChunkSize", self.chunk_size.to_string()),
+            ("resumableChunkNumber", self.chunk_number.to_string()),
+            (
+                "resumableCurrentChunkSize",
+                self.current_chunk_size.to_string(),
+            ),
+        ]
+    }
+}
+
 impl DiscourseClient {
     /// Trigger a backup on the Discourse instance.
     pub fn create_backup(&self) -> Result<()> {
@@ -43,4 +77,132 @@ impl DiscourseClient {
         }
         Ok(())
     }
+
+    /// Ask Discourse to email a backup download link to the API user.
+    pub fn request_backup_download(&self, filename: &str) -> Result<()> {
+        let path = format!("/admin/backups/{}.json", filename);
+        let response = self
+            .put(&path)?
+            .send()
+            .context("requesting backup download link")?;
+        let status = response.status();
+        let text = response
+            .text()
+            .context("reading backup download link response")?;
+        if !status.is_success() {
+            return Err(http_error("backup download link request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Start streaming a backup archive, resuming from `offset` bytes.
+    ///
+    /// The caller checks for `206 Partial Content` to know whether the
+    /// server honoured the range; a plain `200` restarts from zero.
+    pub fn download_backup(&self, filename: &str, token: &str, offset: u64) -> Result<Response> {
+        let path = format!("/admin/backups/{}?token={}", filename, token);
+        let mut request = self.get_request(&path)?;
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+        let response = request.send().context("downloading backup")?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().unwrap_or_default();
+            return Err(http_error("download backup request", status, &text));
+        }
+        Ok(response)
+    }
+
+    /// Check whether a backup upload chunk is already on the server.
+    pub fn backup_chunk_exists(&self, chunk: &BackupChunk) -> Result<bool> {
+        let query = chunk
+            .params()
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join("&");
+        let response = self.get(&format!("/admin/backups/upload?{}", query))?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        let text = response
+            .text()
+            .context("reading backup chunk check response")?;
+        if !status.is_success() {
+            return Err(http_error("backup chunk check request", status, &text));
+        }
+        Ok(true)
+    }
+
+    /// Upload one chunk of a backup archive.
+    pub fn upload_backup_chunk(&self, chunk: &BackupChunk, data: Vec<u8>) -> Result<()> {
+        let part = Part::bytes(data)
+            .file_name(chunk.filename.to_string())
+            .mime_str("application/octet-stream")
+            .context("setting backup chunk mime")?;
+        let form = chunk
+            .params()
+            .into_iter()
+            .fold(Form::new(), |form, (key, value)| form.text(key, value))
+            .part("file", part);
+        let response = self
+            .post("/admin/backups/upload")?
+            .multipart(form)
+            .send()
+            .context("uploading backup chunk")?;
+        let status = response.status();
+        let text = response
+            .text()
+            .context("reading backup chunk upload response")?;
+        if !status.is_success() {
+            return Err(http_error("backup chunk upload request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Fetch the backup/restore operation status.
+    pub fn backup_status(&self) -> Result<Value> {
+        let response = self.get("/admin/backups/status.json")?;
+        let status = response.status();
+        let text = response.text().context("reading backup status response")?;
+        if !status.is_success() {
+            return Err(http_error("backup status request", status, &text));
+        }
+        serde_json::from_str(&text).context("parsing backup status json")
+    }
+
+    /// Fetch the log of the current (or last) backup/restore operation.
+    pub fn backup_logs(&self) -> Result<Value> {
+        let response = self.get("/admin/backups/logs.json")?;
+        let status = response.status();
+        let text = response.text().context("reading backup logs response")?;
+        if !status.is_success() {
+            return Err(http_error("backup logs request", status, &text));
+        }
+        serde_json::from_str(&text).context("parsing backup logs json")
+    }
+
+    /// Cancel the running backup/restore operation.
+    pub fn cancel_backup_operation(&self) -> Result<()> {
+        let response = self.delete("/admin/backups/cancel.json")?;
+        let status = response.status();
+        let text = response.text().context("reading backup cancel response")?;
+        if !status.is_success() {
+            return Err(http_error("cancel backup request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Delete a backup by filename.
+    pub fn delete_backup(&self, filename: &str) -> Result<()> {
+        let response = self.delete(&format!("/admin/backups/{}", filename))?;
+        let status = response.status();
+        let text = response.text().context("reading backup delete response")?;
+        if !status.is_success() {
+            return Err(http_error("delete backup request", status, &text));
+        }
+        Ok(())
+    }
 }
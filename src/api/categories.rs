@@ -1,10 +1,13 @@
 use super::client::DiscourseClient;
 use super::error::http_error;
-use super::models::{CategoriesResponse, CategoryInfo, CategoryResponse, CreateCategoryResponse};
+use super::models::{
+    CategoriesResponse, CategoryGroupPermission, CategoryInfo, CategoryResponse,
+    CategoryShowResponse, CreateCategoryResponse,
+};
 use anyhow::{Context, Result, anyhow};
 use reqwest::StatusCode;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 impl DiscourseClient {
     /// Fetch a category by ID (topics list included).
@@ -67,6 +70,9 @@ impl DiscourseClient {
         if let Some(text_color) = category.text_color.clone() {
             payload.push(("text_color", text_color));
         }
+        if let Some(parent_id) = category.parent_category_id {
+            payload.push(("parent_category_id", parent_id.to_string()));
+        }
         let response = self
             .post("/categories")?
             .form(&payload)
@@ -82,6 +88,67 @@ impl DiscourseClient {
         Ok(body.category.id)
     }
 
+    /// Fetch the group permissions of a category (admin only).
+    pub fn fetch_category_permissions(
+        &self,
+        category_id: u64,
+    ) -> Result<Vec<CategoryGroupPermission>> {
+        let response = self.get(&format!("/c/{}/show.json", category_id))?;
+        let status = response.status();
+        let text = response.text().context("reading category response body")?;
+        if !status.is_success() {
+            return Err(http_error("category permissions request", status, &text));
+        }
+        let body: CategoryShowResponse =
+            serde_json::from_str(&text).context("parsing category permissions json")?;
+        Ok(body.category.group_permissions)
+    }
+
+    /// Update a category's fields.
+    pub fn update_category(&self, category_id: u64, fields: &[(&str, String)]) -> Result<()> {
+        let response = self
+            .put(&format!("/categories/{}.json", category_id))?
+            .form(fields)
+            .send()
+            .context("updating category")?;
+        let status = response.status();
+        let text = response.text().context("reading category response body")?;
+        if !status.is_success() {
+            return Err(http_error("update category request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Delete a category by ID.
+    pub fn delete_category(&self, category_id: u64) -> Result<()> {
+        let response = self.delete(&format!("/categories/{}.json", category_id))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .context("reading delete category response")?;
+        if !status.is_success() {
+            return Err(http_error("delete category request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Set category positions from a category ID to position mapping.
+    pub fn reorder_categories(&self, positions: &BTreeMap<u64, u64>) -> Result<()> {
+        let mapping = serde_json::to_string(positions).context("serializing category mapping")?;
+        let payload = [("mapping", mapping)];
+        let response = self
+            .post("/categories/reorder")?
+            .form(&payload)
+            .send()
+            .context("reordering categories")?;
+        let status = response.status();
+        let text = response.text().context("reading reorder response body")?;
+        if !status.is_success() {
+            return Err(http_error("reorder categories request", status, &text));
+        }
+        Ok(())
+    }
+
     fn fetch_site_categories(&self) -> Result<Vec<CategoryInfo>> {
         let response = self.get("/site.json")?;
         let status = response.status();
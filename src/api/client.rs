@@ -61,6 +61,11 @@ impl DiscourseClient {
         self.client.get(url).send().context("sending request")
     }
 
+    pub(crate) fn get_request(&self, path: &str) -> Result<reqwest::blocking::RequestBuilder> {
+        let url = format!("{}{}", self.baseurl, path);
+        Ok(self.client.get(url))
+    }
+
     pub(crate) fn post(&self, path: &str) -> Result<reqwest::blocking::RequestBuilder> {
         let url = format!("{}{}", self.baseurl, path);
         Ok(self.client.post(url))
@@ -166,6 +171,16 @@ impl DiscourseClient {
         Ok(VersionInfo { version, commit })
     }
 
+    /// Load a page and fail unless it answers with a success status.
+    pub fn check_page(&self, path: &str) -> Result<()> {
+        let response = self.get(path)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("{} request failed with {}", path, status));
+        }
+        Ok(())
+    }
+
     /// Fetch the current Discourse version (best-effort).
     pub fn fetch_version(&self) -> Result<Option<String>> {
         Ok(self.fetch_version_info()?.version)
@@ -81,6 +81,30 @@ pub struct CategoryInfo {
     pub subcategory_list: Vec<CategoryInfo>,
     #[serde(default)]
     pub parent_category_id: Option<u64>,
+    #[serde(default)]
+    pub position: Option<u64>,
+}
+
+/// Category details from the show endpoint (admin view).
+#[derive(Debug, Deserialize)]
+pub struct CategoryShowResponse {
+    pub category: CategoryPermissionsInfo,
+}
+
+/// Category permissions as returned by the show endpoint.
+#[derive(Debug, Deserialize)]
+pub struct CategoryPermissionsInfo {
+    #[serde(default)]
+    pub group_permissions: Vec<CategoryGroupPermission>,
+}
+
+/// A group's permission level on a category.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CategoryGroupPermission {
+    pub permission_type: u64,
+    pub group_name: String,
+    #[serde(default)]
+    pub group_id: Option<u64>,
 }
 
 /// Response payload for categories.json.
@@ -53,6 +53,76 @@ impl DiscourseClient {
         Ok(id)
     }
 
+    /// Import a theme from a git repository and return the created theme JSON.
+    pub fn import_remote_theme(
+        &self,
+        url: &str,
+        branch: Option<&str>,
+        private_key: Option<&str>,
+    ) -> Result<Value> {
+        let mut payload = json!({ "remote": url });
+        if let Some(branch) = branch {
+            payload["branch"] = Value::String(branch.to_string());
+        }
+        if let Some(private_key) = private_key {
+            payload["private_key"] = Value::String(private_key.to_string());
+        }
+        let response = self
+            .post("/admin/themes/import.json")?
+            .json(&payload)
+            .send()
+            .context("importing theme")?;
+        let status = response.status();
+        let text = response.text().context("reading import theme response")?;
+        if !status.is_success() {
+            return Err(http_error("import theme request", status, &text));
+        }
+        let value: Value = serde_json::from_str(&text).context("parsing import theme response")?;
+        Ok(value)
+    }
+
+    /// Download a theme bundle (archive of source files and uploaded assets).
+    pub fn export_theme(&self, theme_id: u64) -> Result<Vec<u8>> {
+        let response = self.get(&format!("/admin/customize/themes/{}/export", theme_id))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().context("reading export theme response")?;
+            return Err(http_error("export theme request", status, &text));
+        }
+        let bytes = response.bytes().context("reading theme bundle")?;
+        Ok(bytes.to_vec())
+    }
+
+    /// Import a theme bundle (.zip or .tar.gz) and return the theme JSON.
+    /// With `theme_id`, the bundle replaces that theme instead of creating one.
+    pub fn import_theme_bundle(
+        &self,
+        filename: &str,
+        bundle: Vec<u8>,
+        theme_id: Option<u64>,
+    ) -> Result<Value> {
+        let part = reqwest::blocking::multipart::Part::bytes(bundle)
+            .file_name(filename.to_string())
+            .mime_str("application/octet-stream")
+            .context("setting theme bundle mime")?;
+        let mut form = reqwest::blocking::multipart::Form::new().part("theme", part);
+        if let Some(theme_id) = theme_id {
+            form = form.text("theme_id", theme_id.to_string());
+        }
+        let response = self
+            .post("/admin/themes/import.json")?
+            .multipart(form)
+            .send()
+            .context("importing theme bundle")?;
+        let status = response.status();
+        let text = response.text().context("reading import theme response")?;
+        if !status.is_success() {
+            return Err(http_error("import theme request", status, &text));
+        }
+        let value: Value = serde_json::from_str(&text).context("parsing import theme response")?;
+        Ok(value)
+    }
+
     /// Delete a theme by ID.
     pub fn delete_theme(&self, theme_id: u64) -> Result<()> {
         let response = self.delete(&format!("/admin/themes/{}.json", theme_id))?;
@@ -64,8 +134,8 @@ impl DiscourseClient {
         Ok(())
     }
 
-    /// Update an existing theme.
-    pub fn update_theme(&self, theme_id: u64, theme: &Value) -> Result<()> {
+    /// Update an existing theme and return the updated theme JSON.
+    pub fn update_theme(&self, theme_id: u64, theme: &Value) -> Result<Value> {
         let payload = json!({ "theme": theme });
         let response = self
             .put(&format!("/admin/themes/{}.json", theme_id))?
@@ -77,6 +147,28 @@ impl DiscourseClient {
         if !status.is_success() {
             return Err(http_error("update theme request", status, &text));
         }
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        let value: Value = serde_json::from_str(&text).context("parsing update theme response")?;
+        Ok(value)
+    }
+
+    /// Update a single theme setting.
+    pub fn update_theme_setting(&self, theme_id: u64, name: &str, value: &str) -> Result<()> {
+        let payload = json!({ "name": name, "value": value });
+        let response = self
+            .put(&format!("/admin/themes/{}/setting.json", theme_id))?
+            .json(&payload)
+            .send()
+            .context("updating theme setting")?;
+        let status = response.status();
+        let text = response
+            .text()
+            .context("reading update theme setting response")?;
+        if !status.is_success() {
+            return Err(http_error("update theme setting request", status, &text));
+        }
         Ok(())
     }
 }
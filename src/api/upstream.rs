@@ -0,0 +1,115 @@
+use anyhow::{Context, Result};
+use reqwest::blocking::Client;
+use reqwest::header::{ACCEPT, AUTHORIZATION, HeaderMap, HeaderValue, USER_AGENT};
+use serde::Deserialize;
+
+use super::error::http_error;
+
+/// Read-only client for the upstream Discourse repository on GitHub.
+#[derive(Clone)]
+pub struct UpstreamClient {
+    api_url: String,
+    repo: String,
+    reference: String,
+    client: Client,
+}
+
+#[derive(Deserialize)]
+struct CompareResponse {
+    ahead_by: u64,
+}
+
+impl UpstreamClient {
+    /// Create a client from `DSC_GITHUB_API_URL`, `DSC_DISCOURSE_UPSTREAM_REPO`,
+    /// `DSC_DISCOURSE_UPSTREAM_REF` and (optionally) `GITHUB_TOKEN`.
+    pub fn from_env() -> Result<Self> {
+        let env_or = |key: &str, default: &str| {
+            std::env::var(key)
+                .ok()
+                .map(|raw| raw.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| default.to_string())
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("dsc"));
+        if let Ok(token) = std::env::var("GITHUB_TOKEN")
+            && !token.trim().is_empty()
+        {
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", token.trim()))
+                    .context("invalid GITHUB_TOKEN")?,
+            );
+        }
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .context("building http client")?;
+        Ok(Self {
+            api_url: env_or("DSC_GITHUB_API_URL", "https://api.github.com")
+                .trim_end_matches('/')
+                .to_string(),
+            repo: env_or("DSC_DISCOURSE_UPSTREAM_REPO", "discourse/discourse"),
+            reference: env_or("DSC_DISCOURSE_UPSTREAM_REF", "tests-passed"),
+            client,
+        })
+    }
+
+    /// The upstream branch or tag installs are compared against.
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    /// The Discourse version at the upstream ref, read from `lib/version.rb`.
+    pub fn latest_version(&self) -> Result<Option<String>> {
+        let url = format!(
+            "{}/repos/{}/contents/lib/version.rb?ref={}",
+            self.api_url, self.repo, self.reference
+        );
+        let response = self
+            .client
+            .get(url)
+            .header(ACCEPT, "application/vnd.github.raw")
+            .send()
+            .context("sending request")?;
+        let status = response.status();
+        let text = response.text().context("reading version.rb")?;
+        if !status.is_success() {
+            return Err(http_error("upstream version request", status, &text));
+        }
+        Ok(parse_version_rb(&text))
+    }
+
+    /// How many commits the upstream ref has that `commit` does not.
+    pub fn commits_behind(&self, commit: &str) -> Result<u64> {
+        let url = format!(
+            "{}/repos/{}/compare/{}...{}",
+            self.api_url, self.repo, commit, self.reference
+        );
+        let response = self
+            .client
+            .get(url)
+            .header(ACCEPT, "application/vnd.github+json")
+            .send()
+            .context("sending request")?;
+        let status = response.status();
+        let text = response.text().context("reading compare response")?;
+        if !status.is_success() {
+            return Err(http_error("upstream compare request", status, &text));
+        }
+        let body: CompareResponse =
+            serde_json::from_str(&text).context("parsing compare response")?;
+        Ok(body.ahead_by)
+    }
+}
+
+/// Extract `STRING = "x.y.z"` from Discourse's `lib/version.rb`.
+fn parse_version_rb(source: &str) -> Option<String> {
+    source.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("STRING")?.trim_start();
+        let rest = rest.strip_prefix('=')?.trim();
+        let value = rest.strip_prefix('"')?;
+        let end = value.find('"')?;
+        Some(value[..end].to_string())
+    })
+}
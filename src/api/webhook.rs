@@ -0,0 +1,29 @@
+use anyhow::{Context, Result};
+use reqwest::blocking::Client;
+use serde_json::Value;
+use std::time::Duration;
+
+use super::error::http_error;
+
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// POST `payload` as JSON to a webhook URL.
+pub fn post_webhook(url: &str, payload: &Value) -> Result<()> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+        .build()
+        .context("building http client")?;
+    let response = client
+        .post(url)
+        .json(payload)
+        .send()
+        // Webhook URLs usually embed a secret; keep them out of error messages.
+        .map_err(|err| err.without_url())
+        .context("sending request")?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().unwrap_or_default();
+        return Err(http_error("webhook request", status, &text));
+    }
+    Ok(())
+}
@@ -47,9 +47,11 @@ pub enum Commands {
         path: Option<PathBuf>,
     },
     /// Run remote OS + Discourse update workflow for one or all Discourses.
+    #[command(subcommand_negates_reqs = true)]
     Update {
         /// Discourse name, or 'all' to update every configured Discourse.
-        name: String,
+        #[arg(required_unless_present_any = ["tags", "exclude", "resume"])]
+        name: Option<String>,
         /// Parallel update mode for `dsc update all`.
         #[arg(long, short = 'p')]
         parallel: bool,
@@ -62,6 +64,44 @@ pub enum Commands {
         /// Auto-confirm changelog posting prompt (non-interactive mode).
         #[arg(long, short = 'y')]
         yes: bool,
+        /// Create a backup and wait for it before updating (abort if it fails).
+        #[arg(long)]
+        backup_first: bool,
+        /// Rebuild web-only containers without downtime (bootstrap, then destroy/start).
+        #[arg(long)]
+        zero_downtime: bool,
+        /// Also write the `dsc update all` report to a file (.json for JSON, otherwise Markdown).
+        #[arg(long)]
+        report: Option<PathBuf>,
+        /// Only update discourses with any of these tags (comma or semicolon separated).
+        #[arg(long)]
+        tags: Option<String>,
+        /// Skip these discourses (comma or semicolon separated names).
+        #[arg(long)]
+        exclude: Option<String>,
+        /// Roll out in waves: one health-checked canary, then batches.
+        #[arg(long)]
+        waves: bool,
+        /// Canary discourse for wave mode (default: the first selected).
+        #[arg(long, requires = "waves")]
+        canary: Option<String>,
+        /// Discourses per batch after the canary.
+        #[arg(long, requires = "waves", default_value_t = 5)]
+        batch_size: usize,
+        /// Stop the rollout once this percentage of updated discourses have failed.
+        #[arg(long, requires = "waves", default_value_t = 20.0)]
+        max_failure_rate: f64,
+        /// Report what an update would do (versions, apt, disk, plugins) without changing anything.
+        #[arg(long, conflicts_with_all = ["resume", "waves", "backup_first", "zero_downtime"])]
+        check: bool,
+        /// Continue the latest unfinished run from each discourse's last completed stage.
+        #[arg(long, conflicts_with_all = ["name", "tags", "exclude", "post_changelog", "waves", "backup_first", "zero_downtime"])]
+        resume: bool,
+        /// Run ID to resume (default: the latest run with unfinished discourses).
+        #[arg(long, requires = "resume")]
+        run: Option<String>,
+        #[command(subcommand)]
+        command: Option<UpdateCommand>,
     },
     /// Manage custom emoji.
     Emoji {
@@ -73,7 +113,7 @@ pub enum Commands {
         #[command(subcommand)]
         command: TopicCommand,
     },
-    /// List/copy/pull/push categories.
+    /// List/copy/pull/push and create/update/move/reorder/delete categories.
     Category {
         #[command(subcommand)]
         command: CategoryCommand,
@@ -133,6 +173,24 @@ pub enum ListCommand {
     Tidy,
 }
 
+#[derive(Subcommand)]
+pub enum UpdateCommand {
+    /// Show in-flight and past update runs with per-discourse stage progress.
+    Status {
+        /// Run ID to show in detail (default: the latest run).
+        #[arg(long)]
+        run: Option<String>,
+    },
+    /// Print the captured SSH output of a discourse's update.
+    Logs {
+        /// Discourse name.
+        name: String,
+        /// Run ID (default: the latest run that included the discourse).
+        #[arg(long)]
+        run: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum EmojiCommand {
     /// Upload one emoji file, or bulk-upload from a directory.
@@ -239,6 +297,108 @@ pub enum CategoryCommand {
         /// Category ID or slug.
         category: String,
     },
+    /// Create a new category.
+    Create {
+        /// Discourse name.
+        discourse: String,
+        /// Category name.
+        name: String,
+        /// Category slug (derived by Discourse when omitted).
+        #[arg(long, short = 's')]
+        slug: Option<String>,
+        /// Background color as hex without '#', e.g. 0088CC.
+        #[arg(long)]
+        color: Option<String>,
+        /// Text color as hex without '#', e.g. FFFFFF.
+        #[arg(long)]
+        text_color: Option<String>,
+        /// Parent category ID or slug.
+        #[arg(long, short = 'p')]
+        parent: Option<String>,
+    },
+    /// Update a category's name, slug or colors.
+    Update {
+        /// Discourse name.
+        discourse: String,
+        /// Category ID or slug.
+        category: String,
+        /// New category name.
+        #[arg(long, short = 'n')]
+        name: Option<String>,
+        /// New category slug.
+        #[arg(long, short = 's')]
+        slug: Option<String>,
+        /// New background color as hex without '#'.
+        #[arg(long)]
+        color: Option<String>,
+        /// New text color as hex without '#'.
+        #[arg(long)]
+        text_color: Option<String>,
+    },
+    /// Move a category under a new parent and/or to a new position.
+    Move {
+        /// Discourse name.
+        discourse: String,
+        /// Category ID or slug.
+        category: String,
+        /// New parent category ID or slug.
+        #[arg(long, short = 'p', conflicts_with = "top_level")]
+        parent: Option<String>,
+        /// Make the category a top-level category.
+        #[arg(long)]
+        top_level: bool,
+        /// New position (requires the fixed_category_positions site setting).
+        #[arg(long)]
+        position: Option<u64>,
+    },
+    /// Apply positions and parents from a YAML category outline.
+    Reorder {
+        /// Discourse name.
+        discourse: String,
+        /// YAML outline file (see `dsc category list --tree --format yaml`).
+        outline: PathBuf,
+        /// Print planned changes without applying them.
+        #[arg(long, short = 'n')]
+        dry_run: bool,
+    },
+    /// Delete a category.
+    Delete {
+        /// Discourse name.
+        discourse: String,
+        /// Category ID or slug.
+        category: String,
+        /// Skip the confirmation prompt.
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    /// Report which groups can see, reply in or create topics in each category.
+    #[command(subcommand_negates_reqs = true)]
+    Permissions {
+        /// Discourse name.
+        #[arg(required = true)]
+        discourse: Option<String>,
+        /// Output format for the permission matrix.
+        #[arg(long, short = 'f', value_enum, default_value = "text")]
+        format: OutputFormat,
+        #[command(subcommand)]
+        command: Option<CategoryPermissionsCommand>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CategoryPermissionsCommand {
+    /// Grant, change or revoke a group's permission on a category.
+    Set {
+        /// Discourse name.
+        discourse: String,
+        /// Category ID or slug.
+        category: String,
+        /// Group name.
+        group: String,
+        /// Permission level (`none` revokes the group's access).
+        #[arg(value_enum)]
+        level: CategoryPermissionLevel,
+    },
 }
 
 #[derive(Subcommand)]
@@ -292,6 +452,12 @@ pub enum BackupCommand {
     Create {
         /// Discourse name.
         discourse: String,
+        /// Follow the backup log until it finishes and print the new filename.
+        #[arg(long, short = 'w')]
+        wait: bool,
+        /// Give up waiting after this many seconds (default: 6 hours).
+        #[arg(long)]
+        timeout: Option<u64>,
     },
     /// List backups.
     List {
@@ -309,7 +475,79 @@ pub enum BackupCommand {
         /// Discourse name.
         discourse: String,
         /// Backup filename/path on the target system.
-        backup_path: String,
+        #[arg(required_unless_present = "from_local")]
+        backup_path: Option<String>,
+        /// Upload a local backup archive, then restore it and follow the log.
+        #[arg(long, conflicts_with = "backup_path")]
+        from_local: Option<PathBuf>,
+        /// Follow the restore log until it finishes (implied by --from-local).
+        #[arg(long, short = 'w', conflicts_with = "from_local")]
+        wait: bool,
+        /// Give up waiting after this many seconds (default: 6 hours).
+        #[arg(long)]
+        timeout: Option<u64>,
+    },
+    /// Show whether a backup or restore is running.
+    Status {
+        /// Discourse name.
+        discourse: String,
+        /// Cancel the running operation.
+        #[arg(long)]
+        cancel: bool,
+    },
+    /// Delete backups outside a retention policy.
+    Prune {
+        /// Discourse name.
+        #[arg(required_unless_present = "local")]
+        discourse: Option<String>,
+        /// Prune backup archives in a local directory instead.
+        #[arg(long, conflicts_with = "discourse")]
+        local: Option<PathBuf>,
+        /// Keep the newest N backups.
+        #[arg(long, default_value_t = 0)]
+        keep_last: usize,
+        /// Keep the newest backup of each of the last N days.
+        #[arg(long, default_value_t = 0)]
+        keep_daily: usize,
+        /// Keep the newest backup of each of the last N months.
+        #[arg(long, default_value_t = 0)]
+        keep_monthly: usize,
+        /// Show what would be kept and deleted without deleting anything.
+        #[arg(long, short = 'n')]
+        dry_run: bool,
+    },
+    /// Upload a local backup archive in resumable chunks.
+    Upload {
+        /// Discourse name.
+        discourse: String,
+        /// Local backup archive (.tar.gz).
+        local_path: PathBuf,
+    },
+    /// Download a backup archive to a local directory.
+    Download {
+        /// Discourse name.
+        discourse: String,
+        /// Backup filename, as shown by `backup list`.
+        filename: String,
+        /// Local directory to download into (defaults to the current directory).
+        dir: Option<PathBuf>,
+        /// Download token from the emailed backup link.
+        #[arg(long, short = 't')]
+        token: Option<String>,
+        /// Stream the archive over SSH instead of HTTP.
+        #[arg(long)]
+        ssh: bool,
+    },
+    /// Download the newest backup from each matching discourse.
+    PullLatest {
+        /// Local directory to download into (one subdirectory per discourse).
+        dir: Option<PathBuf>,
+        /// Filter by tags (comma or semicolon separated).
+        #[arg(long)]
+        tags: Option<String>,
+        /// Stream archives over SSH (required; HTTP downloads need an emailed token).
+        #[arg(long)]
+        ssh: bool,
     },
 }
 
@@ -344,6 +582,65 @@ pub enum PaletteCommand {
         /// Palette ID to update (creates a new palette when omitted).
         palette_id: Option<u64>,
     },
+    /// Generate a complete palette from primary/secondary/tertiary colours.
+    Generate {
+        /// Text colour (hex, e.g. 222222 or #222222).
+        #[arg(long)]
+        primary: String,
+        /// Background colour (hex).
+        #[arg(long)]
+        secondary: String,
+        /// Link/accent colour (hex).
+        #[arg(long)]
+        tertiary: String,
+        /// Palette name.
+        #[arg(long, short = 'n', default_value = "Generated")]
+        name: String,
+        /// Generate the dark variant (primary and secondary swap roles).
+        #[arg(long)]
+        dark: bool,
+        /// Output file (.json or .yml). Prints JSON to stdout when omitted.
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+    },
+    /// Check WCAG contrast ratios of a local palette file.
+    Check {
+        /// Local palette file path (.json or .yml).
+        local_path: PathBuf,
+    },
+    /// Compare two palettes key by key.
+    Diff {
+        /// Palette file path, or <discourse>:<palette ID or name>.
+        left: String,
+        /// Palette file path, or <discourse>:<palette ID or name>.
+        right: String,
+    },
+    /// Copy a palette to another Discourse.
+    Copy {
+        /// Source discourse name.
+        discourse: String,
+        /// Target discourse name (defaults to source when omitted).
+        #[arg(long, short = 't')]
+        target: Option<String>,
+        /// Palette ID.
+        palette_id: u64,
+    },
+    /// Render a palette file as an HTML or SVG swatch sheet with a mock forum.
+    Render {
+        /// Local palette file path (.json or .yml).
+        local_path: PathBuf,
+        /// Output file (.html or .svg). Prints HTML to stdout when omitted.
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+    },
+    /// Create or update a palette by name on every tagged Discourse.
+    Sync {
+        /// Local palette file path (.json or .yml).
+        local_path: PathBuf,
+        /// Optional tag filter (comma/semicolon separated, match-any).
+        #[arg(long, value_name = "tag1,tag2")]
+        tags: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -388,21 +685,33 @@ pub enum ThemeCommand {
         #[arg(long, short = 'v')]
         verbose: bool,
     },
-    /// Install a theme from URL.
+    /// Install a theme or component from a git repository URL.
     Install {
         /// Discourse name.
         discourse: String,
         /// Theme repository URL.
         url: String,
-    },
-    /// Remove a theme by name.
+        /// Branch to install (defaults to the repository's default branch).
+        #[arg(long, short = 'b')]
+        branch: Option<String>,
+        /// SSH private key file for private repositories (deploy key).
+        #[arg(long, short = 'k')]
+        private_key: Option<PathBuf>,
+        /// Run DSC_SSH_THEME_INSTALL_CMD over SSH instead of using the admin API.
+        #[arg(long, conflicts_with_all = ["branch", "private_key"])]
+        ssh: bool,
+    },
+    /// Remove a theme by name or ID.
     Remove {
         /// Discourse name.
         discourse: String,
-        /// Theme name.
+        /// Theme name or ID.
         name: String,
+        /// Run DSC_SSH_THEME_REMOVE_CMD over SSH instead of using the admin API.
+        #[arg(long)]
+        ssh: bool,
     },
-    /// Pull a theme to a local JSON file.
+    /// Pull a theme to a local JSON file (or source directory with --dir).
     Pull {
         /// Discourse name.
         discourse: String,
@@ -410,15 +719,21 @@ pub enum ThemeCommand {
         theme_id: u64,
         /// Destination file path (auto-derived from theme name when omitted).
         local_path: Option<PathBuf>,
+        /// Write the theme as a directory of source files.
+        #[arg(long, short = 'd')]
+        dir: bool,
     },
-    /// Push a local JSON file to create or update a theme.
+    /// Push a local JSON file (or source directory with --dir) to create or update a theme.
     Push {
         /// Discourse name.
         discourse: String,
-        /// Local JSON file path.
+        /// Local JSON file path (or theme directory with --dir).
         local_path: PathBuf,
         /// Theme ID to update (creates a new theme when omitted).
         theme_id: Option<u64>,
+        /// Read the theme from a directory of source files.
+        #[arg(long, short = 'd')]
+        dir: bool,
     },
     /// Duplicate a theme and print the new theme ID.
     Duplicate {
@@ -427,6 +742,157 @@ pub enum ThemeCommand {
         /// Theme ID to duplicate (from `dsc theme list`).
         theme_id: u64,
     },
+    /// Download a theme bundle including uploaded assets.
+    Export {
+        /// Discourse name.
+        discourse: String,
+        /// Theme ID (from `dsc theme list`).
+        theme_id: u64,
+        /// Destination file path (auto-derived from theme name when omitted).
+        local_path: Option<PathBuf>,
+    },
+    /// Import a theme bundle (.zip or .tar.gz) and print the new theme.
+    Import {
+        /// Discourse name.
+        discourse: String,
+        /// Local bundle path.
+        local_path: PathBuf,
+    },
+    /// Copy a theme, with assets and components, to another Discourse.
+    Copy {
+        /// Source discourse name.
+        discourse: String,
+        /// Target discourse name (defaults to source when omitted).
+        #[arg(long, short = 't')]
+        target: Option<String>,
+        /// Theme ID (from `dsc theme list`).
+        theme_id: u64,
+        /// Theme ID on the target to update (defaults to the theme with the same name).
+        #[arg(long)]
+        target_theme: Option<u64>,
+    },
+    /// Watch a theme directory and push changed files on save.
+    Watch {
+        /// Discourse name.
+        discourse: String,
+        /// Theme ID to update (from `dsc theme list`).
+        theme_id: u64,
+        /// Theme directory (layout as written by `dsc theme pull --dir`).
+        dir: PathBuf,
+        /// Milliseconds to wait for writes to settle before pushing.
+        #[arg(long, short = 'd', default_value_t = 500)]
+        debounce: u64,
+        /// Open the forum previewing the theme.
+        #[arg(long, short = 'o')]
+        open: bool,
+    },
+    /// Get and set theme settings.
+    Settings {
+        #[command(subcommand)]
+        command: ThemeSettingsCommand,
+    },
+    /// Add or remove child theme components.
+    Component {
+        #[command(subcommand)]
+        command: ThemeComponentCommand,
+    },
+    /// Make a theme the site default.
+    SetDefault {
+        /// Discourse name.
+        discourse: String,
+        /// Theme ID (from `dsc theme list`).
+        theme_id: u64,
+    },
+    /// Enable a theme or component.
+    Enable {
+        /// Discourse name.
+        discourse: String,
+        /// Theme ID (from `dsc theme list`).
+        theme_id: u64,
+    },
+    /// Disable a theme or component.
+    Disable {
+        /// Discourse name.
+        discourse: String,
+        /// Theme ID (from `dsc theme list`).
+        theme_id: u64,
+    },
+    /// Set whether users can select a theme in their preferences.
+    UserSelectable {
+        /// Discourse name.
+        discourse: String,
+        /// Theme ID (from `dsc theme list`).
+        theme_id: u64,
+        /// true or false.
+        #[arg(action = ArgAction::Set)]
+        selectable: bool,
+    },
+    /// Pull the latest version of a remote (git) theme.
+    UpdateRemote {
+        /// Discourse name.
+        discourse: String,
+        /// Theme ID (from `dsc theme list`).
+        theme_id: u64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ThemeSettingsCommand {
+    /// List all settings for a theme.
+    List {
+        /// Discourse name.
+        discourse: String,
+        /// Theme ID (from `dsc theme list`).
+        theme_id: u64,
+        /// Output format.
+        #[arg(long, short = 'f', value_enum, default_value = "text")]
+        format: ListFormat,
+        /// Show output even when list is empty.
+        #[arg(long, short = 'v')]
+        verbose: bool,
+    },
+    /// Get the current value of a theme setting.
+    Get {
+        /// Discourse name.
+        discourse: String,
+        /// Theme ID (from `dsc theme list`).
+        theme_id: u64,
+        /// Setting name.
+        setting: String,
+    },
+    /// Set a theme setting.
+    Set {
+        /// Discourse name.
+        discourse: String,
+        /// Theme ID (from `dsc theme list`).
+        theme_id: u64,
+        /// Setting name.
+        setting: String,
+        /// Setting value.
+        value: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ThemeComponentCommand {
+    /// Add a component to a parent theme.
+    Add {
+        /// Discourse name.
+        discourse: String,
+        /// Parent theme ID.
+        parent: u64,
+        /// Component theme ID.
+        child: u64,
+    },
+    /// Remove a component from a parent theme.
+    Remove {
+        /// Discourse name.
+        discourse: String,
+        /// Parent theme ID.
+        parent: u64,
+        /// Component theme ID.
+        child: u64,
+    },
 }
 
 #[derive(Subcommand)]
@@ -517,6 +983,19 @@ pub enum ListFormat {
     Yaml,
 }
 
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+pub enum CategoryPermissionLevel {
+    /// See, reply and create topics.
+    Full,
+    /// See and reply.
+    #[value(alias = "create_post")]
+    CreatePost,
+    /// See only.
+    Readonly,
+    /// No access (revokes the group's permission).
+    None,
+}
+
 #[derive(ValueEnum, Clone, Copy)]
 pub enum StructuredFormat {
     /// Pretty JSON.
@@ -1,15 +1,124 @@
-use crate::api::DiscourseClient;
+use crate::api::{BackupChunk, DiscourseClient};
 use crate::cli::OutputFormat;
-use crate::commands::common::{ensure_api_credentials, select_discourse};
-use crate::config::Config;
-use anyhow::{Result, anyhow};
-use std::io;
+use crate::commands::common::{
+    ensure_api_credentials, matches_tags, not_found, parse_tags, select_discourse,
+};
+use crate::commands::notify::{NotifyEvent, NotifyKind, NotifyStage, notify, notify_hooks};
+use crate::commands::update::build_ssh_command;
+use crate::config::{Config, DiscourseConfig, NotifyConfig};
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use indicatif::{ProgressBar, ProgressStyle};
+use reqwest::StatusCode;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, IsTerminal, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::thread;
+use std::time::{Duration, Instant};
 
-pub fn backup_create(config: &Config, discourse_name: &str) -> Result<()> {
+/// Chunk size for backup uploads; matches the Discourse admin uploader.
+const BACKUP_CHUNK_SIZE: u64 = 1024 * 1024;
+const BACKUP_POLL_SECS: u64 = 2;
+const BACKUP_START_GRACE_SECS: u64 = 10;
+const BACKUP_TIMEOUT_SECS: u64 = 6 * 60 * 60;
+
+pub fn backup_create(
+    config: &Config,
+    discourse_name: &str,
+    wait: bool,
+    timeout: Option<u64>,
+) -> Result<()> {
     let discourse = select_discourse(config, Some(discourse_name))?;
     ensure_api_credentials(discourse)?;
     let client = DiscourseClient::new(discourse)?;
+    let hooks = notify_hooks(config, discourse);
+    if !wait {
+        // Nothing follows the backup, so there is no finish to report: say it was queued.
+        return match client.create_backup() {
+            Ok(()) => {
+                notify(
+                    hooks,
+                    &NotifyEvent::new(NotifyKind::Backup, NotifyStage::Queued, &discourse.name),
+                );
+                Ok(())
+            }
+            Err(err) => {
+                notify_backup_failed(hooks, &discourse.name, &err);
+                Err(err)
+            }
+        };
+    }
+    notify(
+        hooks,
+        &NotifyEvent::new(NotifyKind::Backup, NotifyStage::Started, &discourse.name),
+    );
+    match create_backup_and_wait(&client, backup_timeout(timeout)) {
+        Ok(filename) => {
+            notify(
+                hooks,
+                &NotifyEvent::new(NotifyKind::Backup, NotifyStage::Finished, &discourse.name),
+            );
+            println!("{}", filename);
+            Ok(())
+        }
+        Err(err) => {
+            notify_backup_failed(hooks, &discourse.name, &err);
+            Err(err)
+        }
+    }
+}
+
+fn notify_backup_failed(hooks: &[NotifyConfig], name: &str, err: &anyhow::Error) {
+    let mut event = NotifyEvent::new(NotifyKind::Backup, NotifyStage::Failed, name);
+    event.error = Some(format!("{:#}", err));
+    notify(hooks, &event);
+}
+
+/// Create a backup, follow it to completion and return the new archive's filename.
+pub(crate) fn create_backup_and_wait(
+    client: &DiscourseClient,
+    timeout: Duration,
+) -> Result<String> {
+    let before: Vec<String> = backup_entries(&client.list_backups()?)
+        .iter()
+        .map(|backup| backup_filename(backup).to_string())
+        .collect();
+    let previous_log = current_backup_log(client);
     client.create_backup()?;
+    follow_backup_operation(client, &previous_log, timeout)?;
+    let mut backups = backup_entries(&client.list_backups()?);
+    backups.sort_by(|a, b| backup_created_at(b).cmp(&backup_created_at(a)));
+    backups
+        .iter()
+        .map(backup_filename)
+        .find(|filename| !before.iter().any(|name| name == filename))
+        .map(|filename| filename.to_string())
+        .ok_or_else(|| anyhow!("backup finished but no new backup was listed"))
+}
+
+pub fn backup_status(config: &Config, discourse_name: &str, cancel: bool) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let status = client.backup_status()?;
+    let flag = |key: &str| status.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+    let running = flag("is_operation_running");
+    if cancel {
+        if !running {
+            println!("No backup operation running.");
+            return Ok(());
+        }
+        client.cancel_backup_operation()?;
+        println!("cancelled");
+        return Ok(());
+    }
+    println!("{}", if running { "running" } else { "idle" });
+    if running && let Some(line) = current_backup_log(&client).last() {
+        println!("last log: {}", line);
+    }
+    println!("allow_restore: {}", flag("allow_restore"));
+    println!("can_rollback: {}", flag("can_rollback"));
     Ok(())
 }
 
@@ -138,14 +247,624 @@ pub fn backup_list(
     Ok(())
 }
 
-pub fn backup_restore(config: &Config, discourse_name: &str, backup_path: &str) -> Result<()> {
+pub fn backup_restore(
+    config: &Config,
+    discourse_name: &str,
+    backup_path: Option<&str>,
+    from_local: Option<&Path>,
+    wait: bool,
+    timeout: Option<u64>,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let timeout = backup_timeout(timeout);
+    let Some(local_path) = from_local else {
+        let backup_path = backup_path.ok_or_else(|| anyhow!("missing backup to restore"))?;
+        let previous_log = current_backup_log(&client);
+        client.restore_backup(backup_path)?;
+        if wait {
+            follow_backup_operation(&client, &previous_log, timeout)?;
+        }
+        return Ok(());
+    };
+
+    let started = Instant::now();
+    let filename = upload_backup_file(&client, local_path)?;
+    // Uploaded chunks are merged by a background job, so wait for the archive to be listed.
+    while !backup_entries(&client.list_backups()?)
+        .iter()
+        .any(|backup| backup_filename(backup) == filename)
+    {
+        if started.elapsed() >= timeout {
+            return Err(anyhow!(
+                "timed out waiting for {} to appear in the backup list",
+                filename
+            ));
+        }
+        thread::sleep(Duration::from_secs(BACKUP_POLL_SECS));
+    }
+    let allow_restore = client.fetch_site_setting("allow_restore")?;
+    if allow_restore == "true" {
+        return restore_and_follow(&client, &filename, timeout);
+    }
+    client.update_site_setting("allow_restore", "true")?;
+    let result = restore_and_follow(&client, &filename, timeout);
+    // Put `allow_restore` back whether or not the restore worked, so the API
+    // key cannot be used for further restores.
+    let reset = client
+        .update_site_setting("allow_restore", &allow_restore)
+        .with_context(|| format!("resetting allow_restore to {}", allow_restore));
+    result.and(reset)
+}
+
+fn restore_and_follow(client: &DiscourseClient, filename: &str, timeout: Duration) -> Result<()> {
+    let previous_log = current_backup_log(client);
+    client.restore_backup(filename)?;
+    follow_backup_operation(client, &previous_log, timeout)
+}
+
+/// How many backups to keep; the kept set is the union of all three rules.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetentionPolicy {
+    /// Keep the newest N backups.
+    pub keep_last: usize,
+    /// Keep the newest backup of each of the last N days that have one.
+    pub keep_daily: usize,
+    /// Keep the newest backup of each of the last N months that have one.
+    pub keep_monthly: usize,
+}
+
+pub fn backup_prune(
+    config: &Config,
+    discourse_name: Option<&str>,
+    local: Option<&Path>,
+    policy: RetentionPolicy,
+    dry_run: bool,
+) -> Result<()> {
+    if policy.keep_last == 0 && policy.keep_daily == 0 && policy.keep_monthly == 0 {
+        return Err(anyhow!(
+            "refusing to prune without a retention policy; pass --keep-last, --keep-daily or --keep-monthly"
+        ));
+    }
+    if let Some(dir) = local {
+        let backups = local_backups(dir)?;
+        let times: Vec<_> = backups.iter().map(|(_, time)| Some(*time)).collect();
+        let reasons = retention_reasons(&times, &policy);
+        for ((path, _), reasons) in backups.iter().zip(reasons) {
+            let filename = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("");
+            if dry_run {
+                print_prune_plan(filename, &reasons);
+            } else if reasons.is_empty() {
+                fs::remove_file(path).with_context(|| format!("removing {}", path.display()))?;
+                println!("{}", filename);
+            }
+        }
+        return Ok(());
+    }
+
+    let discourse = select_discourse(config, discourse_name)?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let backups = backup_entries(&client.list_backups()?);
+    let times: Vec<_> = backups
+        .iter()
+        .map(|backup| backup_created_at(backup).and_then(parse_backup_time))
+        .collect();
+    let reasons = retention_reasons(&times, &policy);
+    for (backup, reasons) in backups.iter().zip(reasons) {
+        let filename = backup_filename(backup);
+        if dry_run {
+            print_prune_plan(filename, &reasons);
+        } else if reasons.is_empty() {
+            validate_backup_filename(filename)?;
+            client.delete_backup(filename)?;
+            println!("{}", filename);
+        }
+    }
+    Ok(())
+}
+
+fn print_prune_plan(filename: &str, reasons: &[&str]) {
+    if reasons.is_empty() {
+        println!("delete {}", filename);
+    } else {
+        println!("keep {} ({})", filename, reasons.join(", "));
+    }
+}
+
+/// For each backup, the rules that keep it; an empty list means it can go.
+/// Backups without a timestamp are always kept.
+fn retention_reasons(
+    times: &[Option<DateTime<Utc>>],
+    policy: &RetentionPolicy,
+) -> Vec<Vec<&'static str>> {
+    let mut reasons = vec![Vec::new(); times.len()];
+    let mut order: Vec<usize> = Vec::new();
+    for (index, time) in times.iter().enumerate() {
+        match time {
+            Some(_) => order.push(index),
+            None => reasons[index].push("undated"),
+        }
+    }
+    order.sort_by(|a, b| times[*b].cmp(&times[*a]));
+
+    for index in order.iter().take(policy.keep_last) {
+        reasons[*index].push("last");
+    }
+    let rules: [(&str, usize, &str); 2] = [
+        ("daily", policy.keep_daily, "%Y-%m-%d"),
+        ("monthly", policy.keep_monthly, "%Y-%m"),
+    ];
+    for (reason, keep, period) in rules {
+        let mut periods: Vec<String> = Vec::new();
+        for index in &order {
+            if periods.len() >= keep {
+                break;
+            }
+            let Some(time) = times[*index] else {
+                continue;
+            };
+            let key = time.format(period).to_string();
+            if !periods.contains(&key) {
+                periods.push(key);
+                reasons[*index].push(reason);
+            }
+        }
+    }
+    reasons
+}
+
+fn parse_backup_time(raw: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(raw)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Discourse backup archives in a local directory, dated by the timestamp in
+/// the filename (`<db>-YYYY-MM-DD-HHMMSS-v<version>.tar.gz`). Anything else,
+/// including archives without that stamp, is left out so prune never touches it.
+fn local_backups(dir: &Path) -> Result<Vec<(PathBuf, DateTime<Utc>)>> {
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(filename) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !path.is_file() || !(filename.ends_with(".tar.gz") || filename.ends_with(".sql.gz")) {
+            continue;
+        }
+        if let Some(time) = filename_backup_time(filename) {
+            backups.push((path, time));
+        }
+    }
+    backups.sort();
+    Ok(backups)
+}
+
+fn filename_backup_time(filename: &str) -> Option<DateTime<Utc>> {
+    const STAMP_LEN: usize = "YYYY-MM-DD-HHMMSS".len();
+    // chrono accepts signed years and short fields, so check the shape first.
+    let is_stamp = |stamp: &[u8]| {
+        stamp.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 | 10 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+    };
+    filename
+        .as_bytes()
+        .windows(STAMP_LEN)
+        .position(is_stamp)
+        .and_then(|start| {
+            NaiveDateTime::parse_from_str(&filename[start..start + STAMP_LEN], "%Y-%m-%d-%H%M%S")
+                .ok()
+        })
+        .map(|time| time.and_utc())
+}
+
+pub fn backup_upload(config: &Config, discourse_name: &str, local_path: &Path) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let filename = upload_backup_file(&client, local_path)?;
+    println!("{}", filename);
+    Ok(())
+}
+
+pub fn backup_download(
+    config: &Config,
+    discourse_name: &str,
+    filename: &str,
+    dir: Option<&Path>,
+    token: Option<&str>,
+    ssh: bool,
+) -> Result<()> {
     let discourse = select_discourse(config, Some(discourse_name))?;
     ensure_api_credentials(discourse)?;
     let client = DiscourseClient::new(discourse)?;
-    client.restore_backup(backup_path)?;
+    let response = client.list_backups()?;
+    let backup = backup_entries(&response)
+        .into_iter()
+        .find(|backup| backup_filename(backup) == filename)
+        .ok_or_else(|| not_found("backup", filename))?;
+    let dir = dir.unwrap_or_else(|| Path::new("."));
+    let path = download_backup_to(&client, discourse, &backup, dir, token, ssh)?;
+    println!("{}", path.display());
     Ok(())
 }
 
+pub fn backup_pull_latest(
+    config: &Config,
+    tags: Option<&str>,
+    dir: Option<&Path>,
+    ssh: bool,
+) -> Result<()> {
+    // Over HTTP every forum would email a download link and then fail, so
+    // refuse before touching any of them.
+    if !ssh {
+        return Err(anyhow!(
+            "pull-latest requires --ssh; HTTP downloads need a token emailed for each backup"
+        ));
+    }
+    let filter = tags.map(parse_tags).unwrap_or_default();
+    let targets: Vec<_> = config
+        .discourse
+        .iter()
+        .filter(|d| matches_tags(d, &filter))
+        .collect();
+    if targets.is_empty() {
+        return Err(anyhow!("no discourses matched the given tags"));
+    }
+    let root = dir.unwrap_or_else(|| Path::new("."));
+    let mut failures = 0;
+    for discourse in targets {
+        // Backup filenames are only unique per forum, so each gets its own folder.
+        let dir = root.join(&discourse.name);
+        match pull_latest_backup(discourse, &dir) {
+            Ok(Some(path)) => println!("{}", path.display()),
+            Ok(None) => eprintln!("{}: no backups found", discourse.name),
+            Err(err) => {
+                failures += 1;
+                eprintln!("{}: {:#}", discourse.name, err);
+            }
+        }
+    }
+    if failures > 0 {
+        return Err(anyhow!("{} backup download(s) failed", failures));
+    }
+    Ok(())
+}
+
+fn pull_latest_backup(discourse: &DiscourseConfig, dir: &Path) -> Result<Option<PathBuf>> {
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let response = client.list_backups()?;
+    let mut backups = backup_entries(&response);
+    backups.sort_by(|a, b| backup_created_at(b).cmp(&backup_created_at(a)));
+    let Some(latest) = backups.first() else {
+        return Ok(None);
+    };
+    download_backup_to(&client, discourse, latest, dir, None, true).map(Some)
+}
+
+/// Download one backup into `dir`, resuming from a `.part` file when present.
+fn download_backup_to(
+    client: &DiscourseClient,
+    discourse: &DiscourseConfig,
+    backup: &serde_json::Value,
+    dir: &Path,
+    token: Option<&str>,
+    ssh: bool,
+) -> Result<PathBuf> {
+    let filename = backup_filename(backup);
+    validate_backup_filename(filename)?;
+    let expected = backup_size_bytes(backup);
+    let path = dir.join(filename);
+    if path.exists() {
+        let existing = fs::metadata(&path)?.len();
+        if expected.is_none_or(|size| size == existing) {
+            return Ok(path);
+        }
+        return Err(anyhow!(
+            "{} already exists with a different size ({} bytes); remove it to download again",
+            path.display(),
+            existing
+        ));
+    }
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let part_path = dir.join(format!("{}.part", filename));
+    let mut offset = fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+    if expected.is_some_and(|size| offset > size) {
+        offset = 0;
+    }
+
+    // A complete `.part` left behind by an interrupted rename needs no transfer.
+    let complete = offset > 0 && expected == Some(offset);
+    if !complete && ssh {
+        let target = discourse
+            .ssh_host
+            .clone()
+            .unwrap_or_else(|| discourse.name.clone());
+        let command = format!("tail -c +{} '{}/{}'", offset + 1, backup_dir(), filename);
+        let mut child = build_ssh_command(&target, &[])?
+            .arg(&command)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("running ssh to {}: {}", target, command))?;
+        let mut stdout = child.stdout.take().context("capturing ssh output")?;
+        let written = write_backup_stream(&mut stdout, &part_path, offset, expected);
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "ssh command failed for {}: {}",
+                target,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        written?;
+    } else if !complete {
+        let Some(token) = token else {
+            client.request_backup_download(filename)?;
+            return Err(anyhow!(
+                "Discourse only serves backups with an emailed token; a download link for {} \
+                 was sent to {}. Rerun with --token <token>, or use --ssh",
+                filename,
+                discourse.api_username.as_deref().unwrap_or("the API user")
+            ));
+        };
+        let mut response = client.download_backup(filename, token, offset)?;
+        if offset > 0 && response.status() != StatusCode::PARTIAL_CONTENT {
+            offset = 0;
+        }
+        let total = expected.or_else(|| response.content_length().map(|len| len + offset));
+        write_backup_stream(&mut response, &part_path, offset, total)?;
+    }
+
+    let actual = fs::metadata(&part_path)?.len();
+    if let Some(size) = expected
+        && size != actual
+    {
+        return Err(anyhow!(
+            "size mismatch for {}: expected {} bytes, got {}; rerun to resume",
+            filename,
+            size,
+            actual
+        ));
+    }
+    fs::rename(&part_path, &path)
+        .with_context(|| format!("moving {} into place", part_path.display()))?;
+    Ok(path)
+}
+
+/// Upload a local archive in chunks, skipping chunks the server already has.
+fn upload_backup_file(client: &DiscourseClient, local_path: &Path) -> Result<String> {
+    let filename = local_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("invalid backup path: {}", local_path.display()))?
+        .to_string();
+    validate_backup_filename(&filename)?;
+    if !filename.ends_with(".tar.gz") && !filename.ends_with(".gz") {
+        return Err(anyhow!(
+            "backup file should be a .tar.gz or .sql.gz archive: {}",
+            filename
+        ));
+    }
+    let mut file =
+        File::open(local_path).with_context(|| format!("opening {}", local_path.display()))?;
+    let total_size = file.metadata()?.len();
+    if total_size == 0 {
+        return Err(anyhow!("backup file is empty: {}", local_path.display()));
+    }
+    let identifier = format!("{}-{}", total_size, filename);
+    let chunks = total_size.div_ceil(BACKUP_CHUNK_SIZE);
+    let pb = backup_progress_bar(Some(total_size));
+    for chunk_number in 1..=chunks {
+        let start = (chunk_number - 1) * BACKUP_CHUNK_SIZE;
+        let current_chunk_size = BACKUP_CHUNK_SIZE.min(total_size - start);
+        let chunk = BackupChunk {
+            filename: &filename,
+            identifier: &identifier,
+            total_size,
+            chunk_size: BACKUP_CHUNK_SIZE,
+            chunk_number,
+            current_chunk_size,
+        };
+        if !client.backup_chunk_exists(&chunk)? {
+            let mut data = vec![0u8; current_chunk_size as usize];
+            file.seek(SeekFrom::Start(start))?;
+            file.read_exact(&mut data)
+                .with_context(|| format!("reading {}", local_path.display()))?;
+            client
+                .upload_backup_chunk(&chunk, data)
+                .with_context(|| format!("uploading chunk {}/{}", chunk_number, chunks))?;
+        }
+        pb.inc(current_chunk_size);
+    }
+    pb.finish_and_clear();
+    Ok(filename)
+}
+
+/// Print new operation log lines until the server reports it is idle again.
+///
+/// `previous_log` is the log from before the operation was started; it stays
+/// on the server until the new operation clears it and is not echoed.
+fn follow_backup_operation(
+    client: &DiscourseClient,
+    previous_log: &[String],
+    timeout: Duration,
+) -> Result<()> {
+    let started = Instant::now();
+    let mut seen = 0;
+    let mut stale = !previous_log.is_empty();
+    let mut saw_running = false;
+    let mut logs_available = true;
+    let mut failed = false;
+    loop {
+        if logs_available {
+            match client.backup_logs() {
+                Ok(logs) => {
+                    let lines = backup_log_lines(&logs);
+                    stale = stale && lines.first() == previous_log.first();
+                    if !stale {
+                        for line in lines.iter().skip(seen) {
+                            failed |= line.contains("[FAILED]");
+                            eprintln!("{}", line);
+                        }
+                        seen = seen.max(lines.len());
+                    }
+                }
+                Err(err) => {
+                    eprintln!("Backup logs unavailable, following status only: {:#}", err);
+                    logs_available = false;
+                }
+            }
+        }
+        let status = client.backup_status()?;
+        let running = status
+            .get("is_operation_running")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        saw_running |= running;
+        // The operation runs in a forked process and may not be flagged straight away.
+        let settled =
+            saw_running || started.elapsed() >= Duration::from_secs(BACKUP_START_GRACE_SECS);
+        if !running && settled {
+            break;
+        }
+        if started.elapsed() >= timeout {
+            return Err(anyhow!(
+                "timed out after {}s waiting for the backup operation",
+                timeout.as_secs()
+            ));
+        }
+        thread::sleep(Duration::from_secs(BACKUP_POLL_SECS));
+    }
+    if failed {
+        return Err(anyhow!("backup operation failed"));
+    }
+    Ok(())
+}
+
+fn current_backup_log(client: &DiscourseClient) -> Vec<String> {
+    client
+        .backup_logs()
+        .map(|logs| backup_log_lines(&logs))
+        .unwrap_or_default()
+}
+
+pub(crate) fn backup_timeout(timeout: Option<u64>) -> Duration {
+    Duration::from_secs(timeout.unwrap_or(BACKUP_TIMEOUT_SECS))
+}
+
+fn backup_log_lines(logs: &serde_json::Value) -> Vec<String> {
+    let entries = logs
+        .get("logs")
+        .and_then(|v| v.as_array())
+        .or_else(|| logs.as_array())
+        .cloned()
+        .unwrap_or_default();
+    entries
+        .iter()
+        .filter_map(|entry| {
+            if let Some(line) = entry.as_str() {
+                return Some(line.to_string());
+            }
+            let message = entry.get("message").and_then(|v| v.as_str())?;
+            match entry.get("timestamp").and_then(|v| v.as_str()) {
+                Some(timestamp) => Some(format!("[{}] {}", timestamp, message)),
+                None => Some(message.to_string()),
+            }
+        })
+        .collect()
+}
+
+fn backup_progress_bar(total: Option<u64>) -> ProgressBar {
+    if !io::stderr().is_terminal() {
+        return ProgressBar::hidden();
+    }
+    let Some(total) = total else {
+        return ProgressBar::new_spinner();
+    };
+    let pb = ProgressBar::new(total);
+    let style =
+        ProgressStyle::with_template("{bar:40} {bytes}/{total_bytes} {bytes_per_sec} eta {eta}")
+            .unwrap_or_else(|_| ProgressStyle::default_bar());
+    pb.set_style(style);
+    pb
+}
+
+/// Append (or, from offset zero, write) a stream to `path` with a progress bar.
+fn write_backup_stream(
+    reader: &mut impl Read,
+    path: &Path,
+    offset: u64,
+    total: Option<u64>,
+) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(offset > 0)
+        .truncate(offset == 0)
+        .open(path)
+        .with_context(|| format!("opening {}", path.display()))?;
+    let pb = backup_progress_bar(total);
+    pb.set_position(offset);
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buf).context("reading backup stream")?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buf[..read])
+            .with_context(|| format!("writing {}", path.display()))?;
+        pb.inc(read as u64);
+    }
+    file.flush()?;
+    pb.finish_and_clear();
+    Ok(())
+}
+
+fn backup_dir() -> String {
+    std::env::var("DSC_SSH_BACKUP_DIR")
+        .ok()
+        .map(|dir| dir.trim().trim_end_matches('/').to_string())
+        .filter(|dir| !dir.is_empty())
+        .unwrap_or_else(|| "/var/discourse/shared/standalone/backups/default".to_string())
+}
+
+/// Backup names end up in local paths and remote shell commands, so keep them plain.
+fn validate_backup_filename(filename: &str) -> Result<()> {
+    let valid = !filename.is_empty()
+        && !filename.starts_with('.')
+        && filename
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '_'));
+    if !valid {
+        return Err(anyhow!("invalid backup filename: {}", filename));
+    }
+    Ok(())
+}
+
+fn backup_entries(response: &serde_json::Value) -> Vec<serde_json::Value> {
+    response
+        .get("backups")
+        .and_then(|v| v.as_array())
+        .or_else(|| response.as_array())
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn backup_size_bytes(backup: &serde_json::Value) -> Option<u64> {
+    let size = backup.get("size").or_else(|| backup.get("size_bytes"))?;
+    size.as_u64()
+        .or_else(|| size.as_str().and_then(|v| v.trim().parse().ok()))
+}
+
 fn backup_filename(backup: &serde_json::Value) -> &str {
     backup
         .get("filename")
@@ -209,3 +928,76 @@ fn location_from_url(url: &str) -> String {
     }
     trimmed.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        RetentionPolicy, filename_backup_time, local_backups, parse_backup_time, retention_reasons,
+    };
+    use chrono::{DateTime, Utc};
+    use std::fs;
+
+    fn at(raw: &str) -> Option<DateTime<Utc>> {
+        parse_backup_time(raw)
+    }
+
+    #[test]
+    fn retention_keeps_last_daily_and_monthly() {
+        let times = vec![
+            at("2026-03-10T02:00:00Z"),
+            at("2026-03-10T14:00:00Z"),
+            at("2026-03-09T02:00:00Z"),
+            at("2026-02-20T02:00:00Z"),
+            at("2026-01-05T02:00:00Z"),
+            None,
+        ];
+        let policy = RetentionPolicy {
+            keep_last: 1,
+            keep_daily: 2,
+            keep_monthly: 2,
+        };
+        let reasons = retention_reasons(&times, &policy);
+        assert!(
+            reasons[0].is_empty(),
+            "older backup on a kept day is pruned"
+        );
+        assert_eq!(reasons[1], vec!["last", "daily", "monthly"]);
+        assert_eq!(reasons[2], vec!["daily"]);
+        assert_eq!(reasons[3], vec!["monthly"]);
+        assert!(reasons[4].is_empty());
+        assert_eq!(reasons[5], vec!["undated"]);
+    }
+
+    #[test]
+    fn filename_backup_time_reads_discourse_stamp() {
+        let time = filename_backup_time("forum-2026-03-10-020304-v20260301000000.tar.gz");
+        assert_eq!(time, at("2026-03-10T02:03:04Z"));
+        assert_eq!(filename_backup_time("notes.tar.gz"), None);
+    }
+
+    #[test]
+    fn local_backups_only_lists_stamped_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "forum-2026-03-10-020304-v20260301000000.tar.gz",
+            "forum-2026-03-09-020304.sql.gz",
+            "notes.tar.gz",
+            "access-2026-03-10-020304.log.gz",
+            "forum-2026-03-08-020304-v20260301000000.tar.gz.part",
+        ] {
+            fs::write(dir.path().join(name), b"").unwrap();
+        }
+        let names: Vec<_> = local_backups(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "forum-2026-03-09-020304.sql.gz",
+                "forum-2026-03-10-020304-v20260301000000.tar.gz",
+            ]
+        );
+    }
+}
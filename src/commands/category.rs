@@ -1,12 +1,45 @@
-use crate::api::{CategoryInfo, DiscourseClient, TopicSummary};
-use crate::cli::ListFormat;
-use crate::commands::common::{ensure_api_credentials, not_found, select_discourse};
+use crate::api::{CategoryGroupPermission, CategoryInfo, DiscourseClient, TopicSummary};
+use crate::cli::{CategoryPermissionLevel, ListFormat, OutputFormat};
+use crate::commands::common::{confirm, ensure_api_credentials, not_found, select_discourse};
 use crate::config::Config;
 use crate::utils::{ensure_dir, normalize_baseurl, read_markdown, slugify, write_markdown};
 use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use std::io;
 use std::path::Path;
 
+/// A node in a category outline, as printed by `category list --tree` and read by
+/// `category reorder`. Entries are matched by `id`, falling back to `slug`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct CategoryOutline {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    slug: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    children: Vec<CategoryOutline>,
+}
+
+/// One row of the category permission matrix.
+#[derive(Debug, Serialize)]
+struct CategoryPermissionsEntry {
+    id: u64,
+    name: String,
+    permissions: BTreeMap<String, String>,
+}
+
+/// Where a category should sit once an outline has been applied.
+#[derive(Debug, PartialEq)]
+struct OutlinePlacement {
+    id: u64,
+    parent_id: Option<u64>,
+    position: u64,
+}
+
 pub fn category_list(
     config: &Config,
     discourse_name: &str,
@@ -29,7 +62,7 @@ pub fn category_list(
                     println!("No categories found.");
                     return Ok(());
                 }
-                print_category_tree(&flat);
+                print_category_tree(&build_category_outline(&flat));
             } else {
                 let unique = unique_categories(flat);
                 if unique.is_empty() && !verbose {
@@ -43,19 +76,19 @@ pub fn category_list(
             }
         }
         ListFormat::Json => {
-            if tree {
-                return Err(anyhow!("--tree is only supported with --format text"));
-            }
-            let unique = unique_categories(flat);
-            let raw = serde_json::to_string_pretty(&unique)?;
+            let raw = if tree {
+                serde_json::to_string_pretty(&build_category_outline(&flat))?
+            } else {
+                serde_json::to_string_pretty(&unique_categories(flat))?
+            };
             println!("{}", raw);
         }
         ListFormat::Yaml => {
-            if tree {
-                return Err(anyhow!("--tree is only supported with --format text"));
-            }
-            let unique = unique_categories(flat);
-            let raw = serde_yaml::to_string(&unique)?;
+            let raw = if tree {
+                serde_yaml::to_string(&build_category_outline(&flat))?
+            } else {
+                serde_yaml::to_string(&unique_categories(flat))?
+            };
             println!("{}", raw);
         }
     }
@@ -84,6 +117,10 @@ pub fn category_copy(
     copied.name = format!("Copy of {}", category.name);
     copied.slug = format!("{}-copy", category.slug);
     copied.id = None;
+    if target_name != source {
+        // Parent IDs are local to the source forum.
+        copied.parent_category_id = None;
+    }
     let target_client = DiscourseClient::new(target_discourse)?;
     let new_id = target_client.create_category(&copied)?;
     let url = format!(
@@ -176,6 +213,389 @@ pub fn category_push(
     Ok(())
 }
 
+pub fn category_create(
+    config: &Config,
+    discourse_name: &str,
+    name: &str,
+    slug: Option<&str>,
+    color: Option<&str>,
+    text_color: Option<&str>,
+    parent: Option<&str>,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    if name.trim().is_empty() {
+        return Err(anyhow!("missing category name for category create"));
+    }
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let parent_category_id = match parent {
+        Some(parent) => Some(resolve_category_id(&client, parent)?),
+        None => None,
+    };
+    let category = CategoryInfo {
+        name: name.trim().to_string(),
+        slug: slug.map(|s| s.trim().to_string()).unwrap_or_default(),
+        color: color.map(normalize_hex_color),
+        text_color: text_color.map(normalize_hex_color),
+        id: None,
+        subcategory_list: Vec::new(),
+        parent_category_id,
+        position: None,
+    };
+    let new_id = client.create_category(&category)?;
+    println!("{}/c/{}", normalize_baseurl(&discourse.baseurl), new_id);
+    Ok(())
+}
+
+pub fn category_update(
+    config: &Config,
+    discourse_name: &str,
+    category: &str,
+    name: Option<&str>,
+    slug: Option<&str>,
+    color: Option<&str>,
+    text_color: Option<&str>,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    if name.is_none() && slug.is_none() && color.is_none() && text_color.is_none() {
+        return Err(anyhow!(
+            "nothing to update; pass --name, --slug, --color or --text-color"
+        ));
+    }
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let category_id = resolve_category_id(&client, category)?;
+    let existing = find_category(&client, category_id)?;
+    let mut fields = vec![("name", name.unwrap_or(&existing.name).trim().to_string())];
+    if let Some(slug) = slug {
+        fields.push(("slug", slug.trim().to_string()));
+    }
+    if let Some(color) = color {
+        fields.push(("color", normalize_hex_color(color)));
+    }
+    if let Some(text_color) = text_color {
+        fields.push(("text_color", normalize_hex_color(text_color)));
+    }
+    client.update_category(category_id, &fields)?;
+    println!(
+        "{}/c/{}",
+        normalize_baseurl(&discourse.baseurl),
+        category_id
+    );
+    Ok(())
+}
+
+pub fn category_move(
+    config: &Config,
+    discourse_name: &str,
+    category: &str,
+    parent: Option<&str>,
+    top_level: bool,
+    position: Option<u64>,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    if parent.is_none() && !top_level && position.is_none() {
+        return Err(anyhow!(
+            "nothing to move; pass --parent, --top-level or --position"
+        ));
+    }
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let category_id = resolve_category_id(&client, category)?;
+    let existing = find_category(&client, category_id)?;
+    let mut fields = vec![("name", existing.name.clone())];
+    if let Some(parent) = parent {
+        let parent_id = resolve_category_id(&client, parent)?;
+        if parent_id == category_id {
+            return Err(anyhow!("a category cannot be its own parent"));
+        }
+        fields.push(("parent_category_id", parent_id.to_string()));
+    } else if top_level {
+        fields.push(("parent_category_id", String::new()));
+    }
+    if let Some(position) = position {
+        fields.push(("position", position.to_string()));
+    }
+    client.update_category(category_id, &fields)?;
+    println!(
+        "{}/c/{}",
+        normalize_baseurl(&discourse.baseurl),
+        category_id
+    );
+    Ok(())
+}
+
+pub fn category_reorder(
+    config: &Config,
+    discourse_name: &str,
+    outline_path: &Path,
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    let raw = fs::read_to_string(outline_path)
+        .with_context(|| format!("reading {}", outline_path.display()))?;
+    let outline: Vec<CategoryOutline> =
+        serde_yaml::from_str(&raw).context("parsing category outline yaml")?;
+    if outline.is_empty() {
+        return Err(anyhow!("category outline is empty"));
+    }
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let mut flat = Vec::new();
+    for category in client.fetch_categories()? {
+        flatten_categories(&category, &mut flat);
+    }
+    let existing: HashMap<u64, CategoryInfo> = unique_categories(flat)
+        .into_iter()
+        .filter_map(|category| category.id.map(|id| (id, category)))
+        .collect();
+    let placements = plan_category_outline(&outline, &existing)?;
+
+    let mut changes = 0;
+    let mut positions = BTreeMap::new();
+    let mut positions_changed = false;
+    for placement in &placements {
+        let category = &existing[&placement.id];
+        if category.parent_category_id != placement.parent_id {
+            changes += 1;
+            println!(
+                "{} - {}: parent {} -> {}",
+                placement.id,
+                category.name,
+                format_parent(category.parent_category_id),
+                format_parent(placement.parent_id)
+            );
+            if !dry_run {
+                let parent = placement
+                    .parent_id
+                    .map(|id| id.to_string())
+                    .unwrap_or_default();
+                client.update_category(
+                    placement.id,
+                    &[
+                        ("name", category.name.clone()),
+                        ("parent_category_id", parent),
+                    ],
+                )?;
+            }
+        }
+        if category.position != Some(placement.position) {
+            changes += 1;
+            positions_changed = true;
+            println!(
+                "{} - {}: position {} -> {}",
+                placement.id,
+                category.name,
+                category
+                    .position
+                    .map(|p| p.to_string())
+                    .unwrap_or_else(|| "unset".to_string()),
+                placement.position
+            );
+        }
+        positions.insert(placement.id, placement.position);
+    }
+
+    if positions_changed && !dry_run {
+        client.reorder_categories(&positions)?;
+    }
+    if changes == 0 {
+        println!("No changes.");
+    }
+    Ok(())
+}
+
+pub fn category_delete(
+    config: &Config,
+    discourse_name: &str,
+    category: &str,
+    assume_yes: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let category_id = resolve_category_id(&client, category)?;
+    let existing = find_category(&client, category_id)?;
+    let prompt = format!("Delete category {} - {}?", category_id, existing.name);
+    if !assume_yes && !confirm(&prompt)? {
+        return Ok(());
+    }
+    client.delete_category(category_id)?;
+    println!("{}", category_id);
+    Ok(())
+}
+
+pub fn category_permissions(
+    config: &Config,
+    discourse_name: &str,
+    format: OutputFormat,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let mut flat = Vec::new();
+    for category in client.fetch_categories()? {
+        flatten_categories(&category, &mut flat);
+    }
+    let mut entries = Vec::new();
+    for category in unique_categories(flat) {
+        let Some(id) = category.id else {
+            continue;
+        };
+        let permissions = client
+            .fetch_category_permissions(id)?
+            .iter()
+            .map(|permission| {
+                (
+                    permission.group_name.clone(),
+                    permission_level_name(permission).to_string(),
+                )
+            })
+            .collect();
+        entries.push(CategoryPermissionsEntry {
+            id,
+            name: category.name,
+            permissions,
+        });
+    }
+    let groups = permission_groups(&entries);
+
+    match format {
+        OutputFormat::Text => {
+            if entries.is_empty() {
+                println!("No categories found.");
+                return Ok(());
+            }
+            for entry in &entries {
+                println!(
+                    "{} - {}: {}",
+                    entry.id,
+                    entry.name,
+                    format_permissions(&entry.permissions)
+                );
+            }
+        }
+        OutputFormat::Markdown => {
+            for entry in &entries {
+                println!(
+                    "- {} ({}): {}",
+                    entry.name,
+                    entry.id,
+                    format_permissions(&entry.permissions)
+                );
+            }
+        }
+        OutputFormat::MarkdownTable => {
+            let mut header = vec!["Category".to_string()];
+            header.extend(groups.iter().cloned());
+            println!("| {} |", header.join(" | "));
+            println!("|{}", " --- |".repeat(header.len()));
+            for entry in &entries {
+                let mut row = vec![format!("{} ({})", entry.name, entry.id)];
+                for group in &groups {
+                    row.push(
+                        entry
+                            .permissions
+                            .get(group)
+                            .cloned()
+                            .unwrap_or_else(|| "-".to_string()),
+                    );
+                }
+                println!("| {} |", row.join(" | "));
+            }
+        }
+        OutputFormat::Json => {
+            let raw = serde_json::to_string_pretty(&entries)?;
+            println!("{}", raw);
+        }
+        OutputFormat::Yaml => {
+            let raw = serde_yaml::to_string(&entries)?;
+            println!("{}", raw);
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(io::stdout());
+            let mut header = vec!["category_id".to_string(), "category".to_string()];
+            header.extend(groups.iter().cloned());
+            writer.write_record(&header)?;
+            for entry in &entries {
+                let mut row = vec![entry.id.to_string(), entry.name.clone()];
+                for group in &groups {
+                    row.push(entry.permissions.get(group).cloned().unwrap_or_default());
+                }
+                writer.write_record(&row)?;
+            }
+            writer.flush()?;
+        }
+        OutputFormat::Urls => {
+            return Err(anyhow!(
+                "'category permissions' does not support '--format urls'; use text/markdown/markdown-table/json/yaml/csv"
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn category_permissions_set(
+    config: &Config,
+    discourse_name: &str,
+    category: &str,
+    group: &str,
+    level: CategoryPermissionLevel,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    let group = group.trim();
+    if group.is_empty() {
+        return Err(anyhow!("missing group name for category permissions set"));
+    }
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let category_id = resolve_category_id(&client, category)?;
+    let existing = find_category(&client, category_id)?;
+
+    // Discourse replaces the whole permission set on update, so start from the current one.
+    let mut permissions: BTreeMap<String, u64> = client
+        .fetch_category_permissions(category_id)?
+        .into_iter()
+        .map(|permission| (permission.group_name, permission.permission_type))
+        .collect();
+    match permission_type(level) {
+        Some(permission_type) => {
+            permissions.insert(group.to_string(), permission_type);
+        }
+        None => {
+            if permissions.remove(group).is_none() {
+                return Err(anyhow!(
+                    "group {} has no permission on category {}",
+                    group,
+                    category_id
+                ));
+            }
+        }
+    }
+    if permissions.is_empty() {
+        return Err(anyhow!(
+            "cannot revoke the last group permission on category {}; grant another group first",
+            category_id
+        ));
+    }
+
+    let keys: Vec<String> = permissions
+        .keys()
+        .map(|group| format!("permissions[{}]", group))
+        .collect();
+    let mut fields = vec![("name", existing.name.clone())];
+    for (key, permission_type) in keys.iter().zip(permissions.values()) {
+        fields.push((key.as_str(), permission_type.to_string()));
+    }
+    client.update_category(category_id, &fields)?;
+    println!(
+        "{}/c/{}",
+        normalize_baseurl(&discourse.baseurl),
+        category_id
+    );
+    Ok(())
+}
+
 fn resolve_category_id(client: &DiscourseClient, category: &str) -> Result<u64> {
     if let Ok(id) = category.parse::<u64>() {
         return Ok(id);
@@ -202,7 +622,7 @@ fn flatten_categories(category: &CategoryInfo, out: &mut Vec<CategoryInfo>) {
 }
 
 fn unique_categories(flat: Vec<CategoryInfo>) -> Vec<CategoryInfo> {
-    let mut seen = std::collections::HashSet::new();
+    let mut seen = HashSet::new();
     let mut unique = Vec::new();
     for category in flat {
         if let Some(id) = category.id {
@@ -215,9 +635,142 @@ fn unique_categories(flat: Vec<CategoryInfo>) -> Vec<CategoryInfo> {
     unique
 }
 
-fn print_category_tree(categories: &[CategoryInfo]) {
+fn find_category(client: &DiscourseClient, category_id: u64) -> Result<CategoryInfo> {
+    let mut flat = Vec::new();
+    for category in client.fetch_categories()? {
+        flatten_categories(&category, &mut flat);
+    }
+    flat.into_iter()
+        .find(|category| category.id == Some(category_id))
+        .ok_or_else(|| not_found("category", category_id))
+}
+
+fn permission_level_name(permission: &CategoryGroupPermission) -> &'static str {
+    match permission.permission_type {
+        1 => "full",
+        2 => "create_post",
+        3 => "readonly",
+        _ => "unknown",
+    }
+}
+
+fn permission_type(level: CategoryPermissionLevel) -> Option<u64> {
+    match level {
+        CategoryPermissionLevel::Full => Some(1),
+        CategoryPermissionLevel::CreatePost => Some(2),
+        CategoryPermissionLevel::Readonly => Some(3),
+        CategoryPermissionLevel::None => None,
+    }
+}
+
+/// Matrix columns: every group with a permission somewhere, `everyone` first.
+fn permission_groups(entries: &[CategoryPermissionsEntry]) -> Vec<String> {
+    let mut groups: Vec<String> = entries
+        .iter()
+        .flat_map(|entry| entry.permissions.keys().cloned())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    groups.sort_by(|a, b| (a != "everyone", a).cmp(&(b != "everyone", b)));
+    groups
+}
+
+fn format_permissions(permissions: &BTreeMap<String, String>) -> String {
+    if permissions.is_empty() {
+        return "-".to_string();
+    }
+    permissions
+        .iter()
+        .map(|(group, level)| format!("{}: {}", group, level))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Discourse stores colors as bare hex; accept the `#RRGGBB` form too.
+fn normalize_hex_color(color: &str) -> String {
+    color.trim().trim_start_matches('#').to_string()
+}
+
+fn format_parent(parent_id: Option<u64>) -> String {
+    parent_id
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| "none".to_string())
+}
+
+/// Resolve an outline into placements in depth-first order. Positions follow that
+/// order, matching how Discourse numbers categories when reordering in the UI.
+fn plan_category_outline(
+    outline: &[CategoryOutline],
+    existing: &HashMap<u64, CategoryInfo>,
+) -> Result<Vec<OutlinePlacement>> {
+    fn walk(
+        nodes: &[CategoryOutline],
+        parent_id: Option<u64>,
+        existing: &HashMap<u64, CategoryInfo>,
+        seen: &mut HashSet<u64>,
+        out: &mut Vec<OutlinePlacement>,
+    ) -> Result<()> {
+        for node in nodes {
+            let id = resolve_outline_entry(node, existing)?;
+            if !seen.insert(id) {
+                return Err(anyhow!("category {} appears more than once in outline", id));
+            }
+            out.push(OutlinePlacement {
+                id,
+                parent_id,
+                position: out.len() as u64,
+            });
+            walk(&node.children, Some(id), existing, seen, out)?;
+        }
+        Ok(())
+    }
+
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    walk(outline, None, existing, &mut seen, &mut out)?;
+    Ok(out)
+}
+
+fn resolve_outline_entry(
+    node: &CategoryOutline,
+    existing: &HashMap<u64, CategoryInfo>,
+) -> Result<u64> {
+    if let Some(id) = node.id {
+        return if existing.contains_key(&id) {
+            Ok(id)
+        } else {
+            Err(not_found("category", id))
+        };
+    }
+    let slug = node.slug.as_deref().map(str::trim).unwrap_or("");
+    if slug.is_empty() {
+        return Err(anyhow!(
+            "missing id or slug for category outline entry{}",
+            node.name
+                .as_deref()
+                .map(|name| format!(" '{}'", name))
+                .unwrap_or_default()
+        ));
+    }
+    let mut matches: Vec<u64> = existing
+        .values()
+        .filter(|category| category.slug == slug)
+        .filter_map(|category| category.id)
+        .collect();
+    matches.sort_unstable();
+    match matches.as_slice() {
+        [id] => Ok(*id),
+        [] => Err(not_found("category", slug)),
+        _ => Err(anyhow!(
+            "category slug '{}' is ambiguous; use an id in the outline",
+            slug
+        )),
+    }
+}
+
+fn build_category_outline(categories: &[CategoryInfo]) -> Vec<CategoryOutline> {
     let mut ordered_ids = Vec::new();
-    let mut map = std::collections::HashMap::new();
+    let mut map = HashMap::new();
     for category in categories {
         if let Some(id) = category.id {
             if !map.contains_key(&id) {
@@ -227,13 +780,14 @@ fn print_category_tree(categories: &[CategoryInfo]) {
         }
     }
 
-    let mut children: std::collections::HashMap<u64, Vec<u64>> = std::collections::HashMap::new();
-    for category in map.values() {
-        if let (Some(id), Some(parent_id)) = (category.id, category.parent_category_id) {
+    let mut children: HashMap<u64, Vec<u64>> = HashMap::new();
+    for id in &ordered_ids {
+        let category = &map[id];
+        if let Some(parent_id) = category.parent_category_id {
             if map.contains_key(&parent_id) {
                 let entry = children.entry(parent_id).or_default();
-                if !entry.contains(&id) {
-                    entry.push(id);
+                if !entry.contains(id) {
+                    entry.push(*id);
                 }
             }
         }
@@ -249,44 +803,63 @@ fn print_category_tree(categories: &[CategoryInfo]) {
         }
     }
 
-    let mut seen = std::collections::HashSet::new();
-    let last_index = roots.len().saturating_sub(1);
-    for (idx, id) in roots.into_iter().enumerate() {
-        let is_last = idx == last_index;
-        print_category_node(&map, &children, id, "", is_last, &mut seen);
-    }
+    let mut seen = HashSet::new();
+    roots
+        .into_iter()
+        .filter_map(|id| build_outline_node(&map, &children, id, &mut seen))
+        .collect()
 }
 
-fn print_category_node(
-    map: &std::collections::HashMap<u64, CategoryInfo>,
-    children: &std::collections::HashMap<u64, Vec<u64>>,
+fn build_outline_node(
+    map: &HashMap<u64, CategoryInfo>,
+    children: &HashMap<u64, Vec<u64>>,
     id: u64,
-    prefix: &str,
-    is_last: bool,
-    seen: &mut std::collections::HashSet<u64>,
-) {
+    seen: &mut HashSet<u64>,
+) -> Option<CategoryOutline> {
     if !seen.insert(id) {
-        return;
+        return None;
     }
-    if let Some(category) = map.get(&id) {
-        let branch = if is_last {
-            "└── ".to_string()
-        } else {
-            "├── ".to_string()
-        };
-        println!("{}{}{} - {}", prefix, branch, id, category.name);
-        if let Some(child_ids) = children.get(&id) {
-            let new_prefix = if is_last {
-                format!("{}    ", prefix)
-            } else {
-                format!("{}│   ", prefix)
-            };
-            let last_index = child_ids.len().saturating_sub(1);
-            for (idx, child_id) in child_ids.iter().enumerate() {
-                let child_last = idx == last_index;
-                print_category_node(map, children, *child_id, &new_prefix, child_last, seen);
-            }
-        }
+    let category = map.get(&id)?;
+    let child_nodes = children
+        .get(&id)
+        .map(|ids| {
+            ids.iter()
+                .filter_map(|child_id| build_outline_node(map, children, *child_id, seen))
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(CategoryOutline {
+        id: Some(id),
+        slug: Some(category.slug.clone()),
+        name: Some(category.name.clone()),
+        children: child_nodes,
+    })
+}
+
+fn print_category_tree(outline: &[CategoryOutline]) {
+    let last_index = outline.len().saturating_sub(1);
+    for (idx, node) in outline.iter().enumerate() {
+        print_category_node(node, "", idx == last_index);
+    }
+}
+
+fn print_category_node(node: &CategoryOutline, prefix: &str, is_last: bool) {
+    let branch = if is_last { "└── " } else { "├── " };
+    println!(
+        "{}{}{} - {}",
+        prefix,
+        branch,
+        node.id.unwrap_or_default(),
+        node.name.as_deref().unwrap_or("")
+    );
+    let new_prefix = if is_last {
+        format!("{}    ", prefix)
+    } else {
+        format!("{}│   ", prefix)
+    };
+    let last_index = node.children.len().saturating_sub(1);
+    for (idx, child) in node.children.iter().enumerate() {
+        print_category_node(child, &new_prefix, idx == last_index);
     }
 }
 
@@ -319,3 +892,67 @@ fn find_topic_match<'a>(
                 .unwrap_or(false)
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CategoryOutline, OutlinePlacement, plan_category_outline};
+    use crate::api::CategoryInfo;
+    use std::collections::HashMap;
+
+    fn category(id: u64, slug: &str, parent_category_id: Option<u64>) -> (u64, CategoryInfo) {
+        let info = CategoryInfo {
+            name: slug.to_string(),
+            slug: slug.to_string(),
+            color: None,
+            text_color: None,
+            id: Some(id),
+            subcategory_list: Vec::new(),
+            parent_category_id,
+            position: None,
+        };
+        (id, info)
+    }
+
+    #[test]
+    fn outline_assigns_depth_first_positions_and_parents() {
+        let existing: HashMap<_, _> = [
+            category(1, "general", None),
+            category(2, "staff", None),
+            category(3, "meta", Some(2)),
+        ]
+        .into_iter()
+        .collect();
+        let outline: Vec<CategoryOutline> =
+            serde_yaml::from_str("- slug: staff\n- id: 1\n  children:\n    - slug: meta\n")
+                .expect("outline");
+        let placements = plan_category_outline(&outline, &existing).expect("plan");
+        assert_eq!(
+            placements,
+            vec![
+                OutlinePlacement {
+                    id: 2,
+                    parent_id: None,
+                    position: 0
+                },
+                OutlinePlacement {
+                    id: 1,
+                    parent_id: None,
+                    position: 1
+                },
+                OutlinePlacement {
+                    id: 3,
+                    parent_id: Some(1),
+                    position: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn outline_rejects_duplicate_entries() {
+        let existing: HashMap<_, _> = [category(1, "general", None)].into_iter().collect();
+        let outline: Vec<CategoryOutline> =
+            serde_yaml::from_str("- id: 1\n- slug: general\n").expect("outline");
+        assert!(plan_category_outline(&outline, &existing).is_err());
+    }
+}
@@ -2,6 +2,7 @@ use crate::api::DiscourseClient;
 use crate::config::{Config, DiscourseConfig, find_discourse};
 use anyhow::{Context, Result, anyhow};
 use std::fmt::Display;
+use std::io::{self, Write};
 use std::process::Command;
 
 pub fn select_discourse<'a>(
@@ -40,6 +41,15 @@ pub fn missing_config(field: &str, resource: &str, hint: &str) -> anyhow::Error
     )
 }
 
+/// Ask a yes/no question on stdin; anything but an explicit yes declines.
+pub fn confirm(prompt: &str) -> Result<bool> {
+    print!("{} [y/N]: ", prompt);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim(), "y" | "Y" | "yes" | "YES"))
+}
+
 pub fn parse_tags(raw: &str) -> Vec<String> {
     raw.split(|ch| ch == ';' || ch == ',')
         .map(|tag| tag.trim().to_string())
@@ -47,6 +57,20 @@ pub fn parse_tags(raw: &str) -> Vec<String> {
         .collect()
 }
 
+/// True when the discourse has any of the given tags (case-insensitive).
+/// An empty filter matches every discourse.
+pub fn matches_tags(discourse: &DiscourseConfig, filter: &[String]) -> bool {
+    if filter.is_empty() {
+        return true;
+    }
+    let Some(tags) = discourse.tags.as_ref() else {
+        return false;
+    };
+    filter
+        .iter()
+        .any(|tag| tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+}
+
 pub fn fetch_fullname_from_url(baseurl: &str) -> Option<String> {
     let temp = DiscourseConfig {
         name: "temp".to_string(),
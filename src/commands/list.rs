@@ -1,5 +1,5 @@
 use crate::cli::OutputFormat;
-use crate::commands::common::{fetch_fullname_from_url, open_url, parse_tags};
+use crate::commands::common::{fetch_fullname_from_url, matches_tags, open_url, parse_tags};
 use crate::config::{Config, DiscourseConfig, save_config};
 use anyhow::{Context, Result};
 use std::collections::HashMap;
@@ -85,28 +85,11 @@ pub fn list_discourses(
     verbose: bool,
 ) -> Result<()> {
     let filter = tags.map(parse_tags).unwrap_or_default();
-    let matches_filter = |disc: &DiscourseConfig| {
-        if filter.is_empty() {
-            return true;
-        }
-        let disc_tags = disc.tags.as_ref().map(|t| {
-            t.iter()
-                .map(|tag| tag.to_ascii_lowercase())
-                .collect::<Vec<_>>()
-        });
-        let Some(disc_tags) = disc_tags else {
-            return false;
-        };
-        filter.iter().any(|tag| {
-            let tag = tag.to_ascii_lowercase();
-            disc_tags.iter().any(|t| t == &tag)
-        })
-    };
 
     let filtered: Vec<_> = config
         .discourse
         .iter()
-        .filter(|d| matches_filter(d))
+        .filter(|d| matches_tags(d, &filter))
         .collect();
 
     if open {
@@ -7,6 +7,7 @@ pub mod emoji;
 pub mod group;
 pub mod import;
 pub mod list;
+pub mod notify;
 pub mod open;
 pub mod palette;
 pub mod plugin;
@@ -14,3 +15,6 @@ pub mod setting;
 pub mod theme;
 pub mod topic;
 pub mod update;
+pub mod update_check;
+pub mod update_state;
+pub mod update_template;
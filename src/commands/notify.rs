@@ -0,0 +1,149 @@
+use crate::api::post_webhook;
+use crate::config::{Config, DiscourseConfig, NotifyConfig, NotifyFormat};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyKind {
+    Update,
+    Backup,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyStage {
+    /// Accepted by the server, but not followed to completion.
+    Queued,
+    Started,
+    Failed,
+    Finished,
+}
+
+/// One event sent to the configured webhooks; the `json` format posts it as is.
+#[derive(Debug, Serialize)]
+pub struct NotifyEvent {
+    pub event: NotifyKind,
+    pub stage: NotifyStage,
+    pub install: String,
+    pub before_version: Option<String>,
+    pub after_version: Option<String>,
+    /// Last update stage completed before a failure.
+    pub last_stage: Option<String>,
+    pub error: Option<String>,
+    pub run_id: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl NotifyEvent {
+    pub fn new(event: NotifyKind, stage: NotifyStage, install: &str) -> Self {
+        NotifyEvent {
+            event,
+            stage,
+            install: install.to_string(),
+            before_version: None,
+            after_version: None,
+            last_stage: None,
+            error: None,
+            run_id: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// One-line description used by the chat formats.
+    pub fn summary(&self) -> String {
+        let event = match self.event {
+            NotifyKind::Update => "update",
+            NotifyKind::Backup => "backup",
+        };
+        let stage = match self.stage {
+            NotifyStage::Queued => "queued",
+            NotifyStage::Started => "started",
+            NotifyStage::Failed => "failed",
+            NotifyStage::Finished => "finished",
+        };
+        let mut text = format!("dsc: {} of {} {}", event, self.install, stage);
+        match (
+            self.before_version.as_deref(),
+            self.after_version.as_deref(),
+        ) {
+            (Some(before), Some(after)) => text.push_str(&format!(" ({} -> {})", before, after)),
+            (Some(before), None) => text.push_str(&format!(" (from {})", before)),
+            (None, Some(after)) => text.push_str(&format!(" (to {})", after)),
+            (None, None) => {}
+        }
+        if let Some(last_stage) = self.last_stage.as_deref() {
+            text.push_str(&format!(" after stage: {}", last_stage));
+        }
+        if let Some(error) = self.error.as_deref() {
+            text.push_str(&format!(": {}", error));
+        }
+        text
+    }
+}
+
+/// Webhooks for `discourse`: its own `notify` list, else the global one.
+pub fn notify_hooks<'a>(config: &'a Config, discourse: &'a DiscourseConfig) -> &'a [NotifyConfig] {
+    discourse.notify.as_deref().unwrap_or(&config.notify)
+}
+
+/// Send `event` to every hook that wants it. Failures are only warned about,
+/// so an unreachable chat never fails an update.
+pub fn notify(hooks: &[NotifyConfig], event: &NotifyEvent) {
+    for hook in hooks {
+        if event.event == NotifyKind::Backup && !hook.backups {
+            continue;
+        }
+        if let Err(err) = post_webhook(&hook.url, &webhook_payload(hook.format, event)) {
+            eprintln!(
+                "Warning: webhook notification for {} failed: {:#}",
+                event.install, err
+            );
+        }
+    }
+}
+
+fn webhook_payload(format: NotifyFormat, event: &NotifyEvent) -> Value {
+    match format {
+        NotifyFormat::Json => serde_json::to_value(event).unwrap_or(Value::Null),
+        NotifyFormat::Slack => json!({ "text": event.summary() }),
+        NotifyFormat::Mattermost => json!({ "text": event.summary(), "username": "dsc" }),
+        NotifyFormat::Matrix => json!({ "msgtype": "m.notice", "body": event.summary() }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NotifyEvent, NotifyKind, NotifyStage, webhook_payload};
+    use crate::config::NotifyFormat;
+
+    #[test]
+    fn chat_formats_summarise_the_event() {
+        let mut event = NotifyEvent::new(NotifyKind::Update, NotifyStage::Failed, "forum");
+        event.before_version = Some("3.3.0".to_string());
+        event.last_stage = Some("rebuilt".to_string());
+        event.error = Some("smoke checks failed: / returned 502".to_string());
+        let text = "dsc: update of forum failed (from 3.3.0) after stage: rebuilt: \
+            smoke checks failed: / returned 502";
+        assert_eq!(webhook_payload(NotifyFormat::Slack, &event)["text"], text);
+        assert_eq!(
+            webhook_payload(NotifyFormat::Mattermost, &event)["text"],
+            text
+        );
+        assert_eq!(webhook_payload(NotifyFormat::Matrix, &event)["body"], text);
+
+        let raw = webhook_payload(NotifyFormat::Json, &event);
+        assert_eq!(raw["event"], "update");
+        assert_eq!(raw["stage"], "failed");
+        assert_eq!(raw["install"], "forum");
+        assert!(raw["after_version"].is_null());
+
+        let queued = NotifyEvent::new(NotifyKind::Backup, NotifyStage::Queued, "forum");
+        assert_eq!(queued.summary(), "dsc: backup of forum queued");
+        assert_eq!(
+            webhook_payload(NotifyFormat::Json, &queued)["stage"],
+            "queued"
+        );
+    }
+}
@@ -7,8 +7,8 @@ use std::path::Path;
 
 use crate::api::DiscourseClient;
 use crate::cli::ListFormat;
-use crate::commands::common::{ensure_api_credentials, select_discourse};
-use crate::config::Config;
+use crate::commands::common::{ensure_api_credentials, matches_tags, parse_tags, select_discourse};
+use crate::config::{Config, DiscourseConfig};
 use crate::utils::normalize_baseurl;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -119,15 +119,19 @@ pub fn palette_push(
         return Err(anyhow!("palette file contains no colors"));
     }
 
+    for check in contrast_checks(&palette.colors)? {
+        if !check.passes() {
+            eprintln!(
+                "warning: {}/{} contrast {:.2}:1 is below WCAG AA ({}:1)",
+                check.foreground, check.background, check.ratio, WCAG_AA_RATIO
+            );
+        }
+    }
+
     let target_id = palette_id.or(palette.id);
     if let Some(target_id) = target_id {
         client.update_color_scheme(target_id, Some(&palette.name), &palette.colors)?;
-        let url = format!(
-            "{}/admin/customize/colors/{}",
-            normalize_baseurl(&discourse.baseurl),
-            target_id
-        );
-        println!("{}", url);
+        println!("{}", palette_url(discourse, target_id));
     } else {
         if palette.name.trim().is_empty() {
             return Err(anyhow!("missing palette name for palette create"));
@@ -135,17 +139,754 @@ pub fn palette_push(
         let new_id = client.create_color_scheme(&palette.name, &palette.colors)?;
         palette.id = Some(new_id);
         write_palette_file(local_path, &palette)?;
-        let url = format!(
-            "{}/admin/customize/colors/{}",
-            normalize_baseurl(&discourse.baseurl),
-            new_id
+        println!("{}", palette_url(discourse, new_id));
+    }
+
+    Ok(())
+}
+
+/// Compare two palettes (files or `<discourse>:<palette>`) key by key.
+pub fn palette_diff(config: &Config, left: &str, right: &str) -> Result<()> {
+    let left_palette = load_palette_spec(config, left)?;
+    let right_palette = load_palette_spec(config, right)?;
+    let changes = diff_colors(&left_palette.colors, &right_palette.colors);
+    if changes.is_empty() {
+        println!("No differences.");
+        return Ok(());
+    }
+    for (key, before, after) in changes {
+        println!(
+            "{}: {} -> {}",
+            key,
+            before.unwrap_or("(missing)"),
+            after.unwrap_or("(missing)")
         );
-        println!("{}", url);
     }
+    Ok(())
+}
 
+/// Copy a palette to another Discourse and print its URL there.
+pub fn palette_copy(
+    config: &Config,
+    source: &str,
+    target: Option<&str>,
+    palette_id: u64,
+) -> Result<()> {
+    let source_discourse = select_discourse(config, Some(source))?;
+    let target_name = target.unwrap_or(source);
+    let target_discourse = select_discourse(config, Some(target_name))?;
+    ensure_api_credentials(source_discourse)?;
+    ensure_api_credentials(target_discourse)?;
+
+    let source_client = DiscourseClient::new(source_discourse)?;
+    let response = source_client.fetch_color_scheme(palette_id)?;
+    let palette = palette_from_response(&response, palette_id)?;
+    // Keep the name across forums so `palette sync` can match it later.
+    let name = if target_name == source {
+        format!("Copy of {}", palette.name)
+    } else {
+        palette.name.clone()
+    };
+
+    let target_client = DiscourseClient::new(target_discourse)?;
+    let new_id = target_client.create_color_scheme(&name, &palette.colors)?;
+    println!("{}", palette_url(target_discourse, new_id));
+    Ok(())
+}
+
+/// Create or update a palette, matched by name, on every Discourse matching `tags`.
+pub fn palette_sync(config: &Config, local_path: &Path, tags: Option<&str>) -> Result<()> {
+    let palette = read_palette_file(local_path)?;
+    if palette.colors.is_empty() {
+        return Err(anyhow!("palette file contains no colors"));
+    }
+    if palette.name.trim().is_empty() {
+        return Err(anyhow!("missing palette name for palette sync"));
+    }
+
+    let filter = tags.map(parse_tags).unwrap_or_default();
+    let targets: Vec<&DiscourseConfig> = config
+        .discourse
+        .iter()
+        .filter(|d| matches_tags(d, &filter))
+        .collect();
+    if targets.is_empty() {
+        return Err(anyhow!("no discourses matched the tag filter"));
+    }
+
+    let mut failures = 0;
+    for discourse in targets {
+        match sync_palette_to(discourse, &palette) {
+            Ok((action, id)) => {
+                println!(
+                    "{}: {} {}",
+                    discourse.name,
+                    action,
+                    palette_url(discourse, id)
+                );
+            }
+            Err(err) => {
+                failures += 1;
+                eprintln!("{}: {:#}", discourse.name, err);
+            }
+        }
+    }
+    if failures > 0 {
+        return Err(anyhow!("palette sync failed on {} discourse(s)", failures));
+    }
+    Ok(())
+}
+
+fn sync_palette_to(
+    discourse: &DiscourseConfig,
+    palette: &PaletteFile,
+) -> Result<(&'static str, u64)> {
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let response = client.list_color_schemes()?;
+    let existing = scheme_values(&response)
+        .iter()
+        .find(|scheme| scheme_name(scheme) == Some(palette.name.trim()))
+        .and_then(scheme_id);
+    match existing {
+        Some(id) => {
+            client.update_color_scheme(id, Some(&palette.name), &palette.colors)?;
+            Ok(("updated", id))
+        }
+        None => {
+            let id = client.create_color_scheme(&palette.name, &palette.colors)?;
+            Ok(("created", id))
+        }
+    }
+}
+
+/// Load a palette from a local file or a `<discourse>:<palette ID or name>` spec.
+fn load_palette_spec(config: &Config, spec: &str) -> Result<PaletteFile> {
+    let path = Path::new(spec);
+    if path.is_file() {
+        return read_palette_file(path);
+    }
+    let Some((discourse_name, palette)) = spec.split_once(':') else {
+        return Err(anyhow!(
+            "palette not found: {} (expected a file or <discourse>:<palette>)",
+            spec
+        ));
+    };
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let palette_id = match palette.trim().parse::<u64>() {
+        Ok(id) => id,
+        Err(_) => {
+            let response = client.list_color_schemes()?;
+            scheme_values(&response)
+                .iter()
+                .find(|scheme| scheme_name(scheme) == Some(palette.trim()))
+                .and_then(scheme_id)
+                .ok_or_else(|| anyhow!("palette not found on {}: {}", discourse_name, palette))?
+        }
+    };
+    let response = client.fetch_color_scheme(palette_id)?;
+    palette_from_response(&response, palette_id)
+}
+
+/// Keys whose colours differ, with the left and right values.
+fn diff_colors<'a>(
+    left: &'a BTreeMap<String, String>,
+    right: &'a BTreeMap<String, String>,
+) -> Vec<(&'a str, Option<&'a str>, Option<&'a str>)> {
+    let normalize = |value: &str| value.trim().trim_start_matches('#').to_ascii_lowercase();
+    let mut keys: Vec<&String> = left.keys().chain(right.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter_map(|key| {
+            let before = left.get(key).map(String::as_str);
+            let after = right.get(key).map(String::as_str);
+            if before.map(normalize) == after.map(normalize) {
+                return None;
+            }
+            Some((key.as_str(), before, after))
+        })
+        .collect()
+}
+
+fn scheme_values(response: &Value) -> Vec<Value> {
+    response
+        .as_array()
+        .or_else(|| response.get("color_schemes").and_then(|v| v.as_array()))
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn scheme_id(scheme: &Value) -> Option<u64> {
+    scheme
+        .get("id")
+        .or_else(|| scheme.get("color_scheme_id"))
+        .and_then(|v| v.as_u64())
+}
+
+fn scheme_name(scheme: &Value) -> Option<&str> {
+    scheme
+        .get("name")
+        .or_else(|| scheme.get("color_scheme_name"))
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+}
+
+fn palette_url(discourse: &DiscourseConfig, palette_id: u64) -> String {
+    format!(
+        "{}/admin/customize/colors/{}",
+        normalize_baseurl(&discourse.baseurl),
+        palette_id
+    )
+}
+
+/// Derive a complete palette from the three base colours.
+pub fn palette_generate(
+    primary: &str,
+    secondary: &str,
+    tertiary: &str,
+    name: &str,
+    dark: bool,
+    output: Option<&Path>,
+) -> Result<()> {
+    let colors = generate_colors(
+        parse_hex(primary)?,
+        parse_hex(secondary)?,
+        parse_hex(tertiary)?,
+        dark,
+    );
+    let palette = PaletteFile {
+        id: None,
+        name: name.to_string(),
+        colors,
+    };
+    for check in contrast_checks(&palette.colors)? {
+        if !check.passes() {
+            eprintln!(
+                "warning: {}/{} contrast {:.2}:1 is below WCAG AA ({}:1)",
+                check.foreground, check.background, check.ratio, WCAG_AA_RATIO
+            );
+        }
+    }
+    match output {
+        Some(path) => {
+            write_palette_file(path, &palette)?;
+            println!("{}", path.display());
+        }
+        None => println!("{}", serde_json::to_string_pretty(&palette)?),
+    }
+    Ok(())
+}
+
+/// Print WCAG contrast ratios for a palette file; fails when any pair is below AA.
+pub fn palette_check(local_path: &Path) -> Result<()> {
+    let palette = read_palette_file(local_path)?;
+    let checks = contrast_checks(&palette.colors)?;
+    if checks.is_empty() {
+        return Err(anyhow!(
+            "palette has none of the colour pairs to check ({})",
+            CONTRAST_PAIRS
+                .iter()
+                .map(|(fg, bg)| format!("{}/{}", fg, bg))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    let mut failures = 0;
+    for check in &checks {
+        println!(
+            "{}/{}: {:.2}:1 {}",
+            check.foreground,
+            check.background,
+            check.ratio,
+            check.level()
+        );
+        if !check.passes() {
+            failures += 1;
+        }
+    }
+    if failures > 0 {
+        return Err(anyhow!(
+            "{} colour pair(s) below WCAG AA contrast ({}:1)",
+            failures,
+            WCAG_AA_RATIO
+        ));
+    }
+    Ok(())
+}
+
+/// Render a palette file as labelled swatches plus a mock Discourse header and topic list.
+pub fn palette_render(local_path: &Path, output: Option<&Path>) -> Result<()> {
+    let palette = read_palette_file(local_path)?;
+    if palette.colors.is_empty() {
+        return Err(anyhow!("palette file contains no colors"));
+    }
+    let svg = render_palette_svg(&palette)?;
+    let as_svg = output
+        .and_then(|path| path.extension())
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+    let content = if as_svg {
+        svg
+    } else {
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body style=\"margin:0;background:#f4f4f4\">\n{}</body>\n</html>\n",
+            xml_escape(&palette.name),
+            svg
+        )
+    };
+    match output {
+        Some(path) => {
+            if let Some(parent) = path.parent()
+                && !parent.as_os_str().is_empty()
+            {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("creating {}", parent.display()))?;
+            }
+            fs::write(path, content).with_context(|| format!("writing {}", path.display()))?;
+            println!("{}", path.display());
+        }
+        None => print!("{}", content),
+    }
     Ok(())
 }
 
+/// Discourse's stock Light colours, used for keys missing from a palette.
+const DEFAULT_COLORS: &[(&str, &str)] = &[
+    ("primary", "222222"),
+    ("secondary", "ffffff"),
+    ("tertiary", "0088cc"),
+    ("quaternary", "e45735"),
+    ("header_background", "ffffff"),
+    ("header_primary", "333333"),
+    ("highlight", "ffff4d"),
+    ("danger", "c80001"),
+    ("success", "009900"),
+    ("love", "fa6c8d"),
+    ("selected", "d1f0ff"),
+    ("hover", "f2f2f2"),
+];
+
+const SWATCH_COLUMNS: usize = 4;
+const SWATCH_WIDTH: usize = 220;
+const SWATCH_HEIGHT: usize = 80;
+const SWATCH_GAP: usize = 20;
+const SHEET_WIDTH: usize = SWATCH_COLUMNS * (SWATCH_WIDTH + SWATCH_GAP) + SWATCH_GAP;
+
+fn render_palette_svg(palette: &PaletteFile) -> Result<String> {
+    let mut colors = BTreeMap::new();
+    for (key, value) in DEFAULT_COLORS {
+        colors.insert(key.to_string(), parse_hex(value)?);
+    }
+    for (key, value) in &palette.colors {
+        let color = parse_hex(value).with_context(|| format!("invalid {} colour", key))?;
+        colors.insert(key.clone(), color);
+    }
+    let color = |key: &str| colors[key];
+    let fill = |key: &str| format!("#{}", colors[key].hex());
+
+    let mut body = String::new();
+    let mut y = SWATCH_GAP;
+    body.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\" font-size=\"20\" font-weight=\"bold\" fill=\"#222222\">{}</text>\n",
+        SWATCH_GAP,
+        y + 20,
+        xml_escape(&palette.name)
+    ));
+    y += 40;
+
+    // One swatch per key in the file.
+    for (index, (key, value)) in palette.colors.iter().enumerate() {
+        let swatch = colors[key.as_str()];
+        let x = SWATCH_GAP + (index % SWATCH_COLUMNS) * (SWATCH_WIDTH + SWATCH_GAP);
+        let top = y + (index / SWATCH_COLUMNS) * (SWATCH_HEIGHT + SWATCH_GAP);
+        let label = readable_text(swatch);
+        body.push_str(&format!(
+            "<rect x=\"{x}\" y=\"{top}\" width=\"{SWATCH_WIDTH}\" height=\"{SWATCH_HEIGHT}\" rx=\"6\" fill=\"#{}\" stroke=\"#cccccc\"/>\n",
+            swatch.hex()
+        ));
+        body.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"14\" font-weight=\"bold\" fill=\"{label}\">{}</text>\n",
+            x + 12,
+            top + 30,
+            xml_escape(key)
+        ));
+        body.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"13\" fill=\"{label}\">{}</text>\n",
+            x + 12,
+            top + 56,
+            xml_escape(value.trim())
+        ));
+    }
+    let rows = palette.colors.len().div_ceil(SWATCH_COLUMNS);
+    y += rows * (SWATCH_HEIGHT + SWATCH_GAP) + SWATCH_GAP;
+
+    // Mock header.
+    let mock_x = SWATCH_GAP;
+    let mock_width = SHEET_WIDTH - 2 * SWATCH_GAP;
+    body.push_str(&format!(
+        "<rect x=\"{mock_x}\" y=\"{y}\" width=\"{mock_width}\" height=\"420\" fill=\"{}\" stroke=\"#cccccc\"/>\n",
+        fill("secondary")
+    ));
+    body.push_str(&format!(
+        "<rect x=\"{mock_x}\" y=\"{y}\" width=\"{mock_width}\" height=\"60\" fill=\"{}\"/>\n",
+        fill("header_background")
+    ));
+    body.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\" font-size=\"20\" font-weight=\"bold\" fill=\"{}\">{}</text>\n",
+        mock_x + 20,
+        y + 38,
+        fill("header_primary"),
+        xml_escape(&palette.name)
+    ));
+    body.push_str(&format!(
+        "<circle cx=\"{}\" cy=\"{}\" r=\"12\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>\n",
+        mock_x + mock_width - 40,
+        y + 30,
+        fill("header_primary")
+    ));
+    y += 80;
+
+    // Navigation pills.
+    body.push_str(&format!(
+        "<rect x=\"{}\" y=\"{y}\" width=\"80\" height=\"32\" rx=\"4\" fill=\"{}\"/>\n",
+        mock_x + 20,
+        fill("quaternary")
+    ));
+    body.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\" font-size=\"14\" fill=\"{}\">Latest</text>\n",
+        mock_x + 38,
+        y + 21,
+        readable_text(color("quaternary"))
+    ));
+    for (offset, label) in [(110, "New (3)"), (200, "Unread"), (280, "Top")] {
+        body.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"14\" fill=\"{}\">{}</text>\n",
+            mock_x + offset,
+            y + 21,
+            fill("primary"),
+            label
+        ));
+    }
+    y += 52;
+
+    // Topic list.
+    let rows = [
+        ("Welcome to the forum", None, "success", "12"),
+        (
+            "Release notes for the new version",
+            Some("highlight"),
+            "danger",
+            "48",
+        ),
+        (
+            "How do I change my avatar?",
+            Some("selected"),
+            "success",
+            "3",
+        ),
+        ("Show us your setup", Some("hover"), "danger", "27"),
+    ];
+    for (title, background, badge, replies) in rows {
+        if let Some(background) = background {
+            body.push_str(&format!(
+                "<rect x=\"{mock_x}\" y=\"{y}\" width=\"{mock_width}\" height=\"56\" fill=\"{}\"/>\n",
+                fill(background)
+            ));
+        }
+        body.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"16\" fill=\"{}\" text-decoration=\"underline\">{}</text>\n",
+            mock_x + 20,
+            y + 24,
+            fill("tertiary"),
+            title
+        ));
+        body.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"10\" height=\"10\" fill=\"{}\"/>\n",
+            mock_x + 20,
+            y + 36,
+            fill(badge)
+        ));
+        body.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"12\" fill=\"{}\">{}</text>\n",
+            mock_x + 36,
+            y + 46,
+            fill("primary"),
+            badge
+        ));
+        body.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"14\" fill=\"{}\">{}</text>\n",
+            mock_x + mock_width - 110,
+            y + 32,
+            fill("primary"),
+            replies
+        ));
+        body.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"14\" fill=\"{}\">&#9829;</text>\n",
+            mock_x + mock_width - 60,
+            y + 32,
+            fill("love")
+        ));
+        body.push_str(&format!(
+            "<line x1=\"{mock_x}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\"/>\n",
+            y + 56,
+            mock_x + mock_width,
+            y + 56,
+            fill("hover")
+        ));
+        y += 56;
+    }
+    y += 60;
+
+    Ok(format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{SHEET_WIDTH}\" height=\"{y}\" viewBox=\"0 0 {SHEET_WIDTH} {y}\" font-family=\"Helvetica, Arial, sans-serif\">\n<rect width=\"100%\" height=\"100%\" fill=\"#f4f4f4\"/>\n{body}</svg>\n"
+    ))
+}
+
+/// Black or white, whichever reads better on `background`.
+fn readable_text(background: Rgb) -> &'static str {
+    let black = Rgb(0, 0, 0);
+    let white = Rgb(0xff, 0xff, 0xff);
+    if contrast_ratio(background, white) >= contrast_ratio(background, black) {
+        "#ffffff"
+    } else {
+        "#000000"
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Foreground/background keys checked for contrast.
+const CONTRAST_PAIRS: &[(&str, &str)] = &[
+    ("primary", "secondary"),
+    ("header_primary", "header_background"),
+    ("tertiary", "secondary"),
+];
+
+const WCAG_AA_RATIO: f64 = 4.5;
+const WCAG_AA_LARGE_RATIO: f64 = 3.0;
+const WCAG_AAA_RATIO: f64 = 7.0;
+
+struct ContrastCheck {
+    foreground: &'static str,
+    background: &'static str,
+    ratio: f64,
+}
+
+impl ContrastCheck {
+    fn passes(&self) -> bool {
+        self.ratio >= WCAG_AA_RATIO
+    }
+
+    fn level(&self) -> &'static str {
+        if self.ratio >= WCAG_AAA_RATIO {
+            "AAA"
+        } else if self.ratio >= WCAG_AA_RATIO {
+            "AA"
+        } else if self.ratio >= WCAG_AA_LARGE_RATIO {
+            "FAIL (AA large text only)"
+        } else {
+            "FAIL"
+        }
+    }
+}
+
+/// Contrast ratios for each pair present in the palette.
+fn contrast_checks(colors: &BTreeMap<String, String>) -> Result<Vec<ContrastCheck>> {
+    let mut out = Vec::new();
+    for (foreground, background) in CONTRAST_PAIRS {
+        let (Some(fg), Some(bg)) = (colors.get(*foreground), colors.get(*background)) else {
+            continue;
+        };
+        let fg = parse_hex(fg).with_context(|| format!("invalid {} colour", foreground))?;
+        let bg = parse_hex(bg).with_context(|| format!("invalid {} colour", background))?;
+        out.push(ContrastCheck {
+            foreground,
+            background,
+            ratio: contrast_ratio(fg, bg),
+        });
+    }
+    Ok(out)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rgb(u8, u8, u8);
+
+impl Rgb {
+    /// Hex form as stored by Discourse (lowercase, no leading '#').
+    fn hex(self) -> String {
+        format!("{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+fn parse_hex(value: &str) -> Result<Rgb> {
+    let hex = value.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!("invalid hex colour: {}", value));
+    }
+    let expanded: String = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => return Err(anyhow!("invalid hex colour: {}", value)),
+    };
+    let channel = |i: usize| {
+        u8::from_str_radix(&expanded[i..i + 2], 16)
+            .map_err(|_| anyhow!("invalid hex colour: {}", value))
+    };
+    Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Blend `a` towards `b` by `weight` (0.0 = a, 1.0 = b).
+fn mix(a: Rgb, b: Rgb, weight: f64) -> Rgb {
+    let blend = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * weight).round() as u8;
+    Rgb(blend(a.0, b.0), blend(a.1, b.1), blend(a.2, b.2))
+}
+
+/// WCAG relative luminance.
+fn relative_luminance(color: Rgb) -> f64 {
+    let linear = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(color.0) + 0.7152 * linear(color.1) + 0.0722 * linear(color.2)
+}
+
+/// WCAG contrast ratio between two colours (1.0 to 21.0).
+fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    let (lighter, darker) = if la > lb { (la, lb) } else { (lb, la) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+fn to_hsl(color: Rgb) -> (f64, f64, f64) {
+    let r = color.0 as f64 / 255.0;
+    let g = color.1 as f64 / 255.0;
+    let b = color.2 as f64 / 255.0;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    if max == min {
+        return (0.0, 0.0, l);
+    }
+    let d = max - min;
+    let s = if l > 0.5 {
+        d / (2.0 - max - min)
+    } else {
+        d / (max + min)
+    };
+    let h = if max == r {
+        (g - b) / d + if g < b { 6.0 } else { 0.0 }
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h * 60.0, s, l)
+}
+
+fn from_hsl(h: f64, s: f64, l: f64) -> Rgb {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let hp = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (hp % 2.0 - 1.0).abs());
+    let (r, g, b) = match hp as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let channel = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Rgb(channel(r), channel(g), channel(b))
+}
+
+/// Lighten or darken `color` (away from `background`) until it reaches `min_ratio`.
+fn ensure_contrast(color: Rgb, background: Rgb, min_ratio: f64) -> Rgb {
+    let (h, s, mut l) = to_hsl(color);
+    let step = if relative_luminance(background) > 0.18 {
+        -0.02
+    } else {
+        0.02
+    };
+    let mut out = color;
+    while contrast_ratio(out, background) < min_ratio && (0.0..=1.0).contains(&(l + step)) {
+        l += step;
+        out = from_hsl(h, s, l);
+    }
+    out
+}
+
+/// Derive the full set of Discourse palette colours from the base colours.
+/// The dark variant swaps primary (text) and secondary (background).
+fn generate_colors(
+    primary: Rgb,
+    secondary: Rgb,
+    tertiary: Rgb,
+    dark: bool,
+) -> BTreeMap<String, String> {
+    let (text, background) = if dark {
+        (secondary, primary)
+    } else {
+        (primary, secondary)
+    };
+    let tertiary = ensure_contrast(tertiary, background, WCAG_AA_RATIO);
+    let (h, s, l) = to_hsl(tertiary);
+    let quaternary = ensure_contrast(from_hsl(h + 180.0, s, l), background, WCAG_AA_LARGE_RATIO);
+    // Status colours follow Discourse's stock Light/Dark schemes.
+    let (highlight, danger, success, love) = if dark {
+        (
+            Rgb(0xa8, 0x71, 0x37),
+            Rgb(0xe4, 0x57, 0x35),
+            Rgb(0x1c, 0xa5, 0x51),
+            Rgb(0xfa, 0x6c, 0x8d),
+        )
+    } else {
+        (
+            Rgb(0xff, 0xff, 0x4d),
+            Rgb(0xc8, 0x00, 0x01),
+            Rgb(0x00, 0x99, 0x00),
+            Rgb(0xfa, 0x6c, 0x8d),
+        )
+    };
+
+    let mut colors = BTreeMap::new();
+    let mut set = |key: &str, color: Rgb| {
+        colors.insert(key.to_string(), color.hex());
+    };
+    set("primary", text);
+    set("secondary", background);
+    set("tertiary", tertiary);
+    set("quaternary", quaternary);
+    set("header_background", background);
+    set("header_primary", text);
+    set("highlight", highlight);
+    set(
+        "danger",
+        ensure_contrast(danger, background, WCAG_AA_LARGE_RATIO),
+    );
+    set(
+        "success",
+        ensure_contrast(success, background, WCAG_AA_LARGE_RATIO),
+    );
+    set("love", love);
+    set("selected", mix(background, tertiary, 0.15));
+    set("hover", mix(background, text, 0.07));
+    colors
+}
+
 fn palette_from_response(response: &Value, fallback_id: u64) -> Result<PaletteFile> {
     let scheme = response.get("color_scheme").unwrap_or(response);
     let id = scheme
@@ -231,3 +972,80 @@ fn is_yaml(path: &Path) -> bool {
         Some("yml") | Some("yaml")
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CONTRAST_PAIRS, ContrastCheck, PaletteFile, contrast_checks, contrast_ratio, diff_colors,
+        generate_colors, parse_hex, render_palette_svg,
+    };
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn contrast_ratio_matches_wcag_reference_values() {
+        let black = parse_hex("#000").unwrap();
+        let white = parse_hex("ffffff").unwrap();
+        assert!((contrast_ratio(black, white) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(white, white) - 1.0).abs() < 0.01);
+        let grey = parse_hex("777777").unwrap();
+        assert!((contrast_ratio(grey, white) - 4.48).abs() < 0.01);
+    }
+
+    #[test]
+    fn generated_palettes_pass_contrast_checks() {
+        let primary = parse_hex("222222").unwrap();
+        let secondary = parse_hex("ffffff").unwrap();
+        // A light accent that fails on white until adjusted.
+        let tertiary = parse_hex("66ccff").unwrap();
+        for dark in [false, true] {
+            let colors = generate_colors(primary, secondary, tertiary, dark);
+            let checks = contrast_checks(&colors).unwrap();
+            assert_eq!(checks.len(), CONTRAST_PAIRS.len());
+            assert!(checks.iter().all(ContrastCheck::passes), "dark={dark}");
+        }
+    }
+
+    #[test]
+    fn diff_colors_ignores_case_and_hash() {
+        let left = BTreeMap::from([
+            ("primary".to_string(), "#AABBCC".to_string()),
+            ("secondary".to_string(), "ffffff".to_string()),
+        ]);
+        let right = BTreeMap::from([
+            ("primary".to_string(), "aabbcc".to_string()),
+            ("tertiary".to_string(), "0088cc".to_string()),
+        ]);
+        let changes = diff_colors(&left, &right);
+        assert_eq!(
+            changes,
+            vec![
+                ("secondary", Some("ffffff"), None),
+                ("tertiary", None, Some("0088cc")),
+            ]
+        );
+    }
+
+    #[test]
+    fn render_escapes_names_and_fills_missing_keys() {
+        let palette = PaletteFile {
+            id: None,
+            name: "A <b> & c".to_string(),
+            colors: BTreeMap::from([("primary".to_string(), "#123456".to_string())]),
+        };
+        let svg = render_palette_svg(&palette).unwrap();
+        assert!(svg.contains("A &lt;b&gt; &amp; c"));
+        assert!(svg.contains("fill=\"#123456\""));
+        // The mock header falls back to Discourse's default header colour.
+        assert!(svg.contains("fill=\"#333333\""));
+    }
+
+    #[test]
+    fn parse_hex_rejects_invalid_values() {
+        assert!(parse_hex("12345").is_err());
+        assert!(parse_hex("gggggg").is_err());
+        assert!(parse_hex("aé123").is_err());
+        assert!(parse_hex("#ééé").is_err());
+        assert!(parse_hex("+f0000").is_err());
+        assert!(parse_hex("#+f+f+f").is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::api::DiscourseClient;
 use crate::cli::ListFormat;
-use crate::commands::common::{ensure_api_credentials, parse_tags, select_discourse};
-use crate::config::{Config, DiscourseConfig};
+use crate::commands::common::{ensure_api_credentials, matches_tags, parse_tags, select_discourse};
+use crate::config::Config;
 use anyhow::{anyhow, Result};
 use serde::Serialize;
 
@@ -25,26 +25,9 @@ pub fn set_site_setting(
 
     // No specific discourse - use tag filter across all discourses.
     let filter = tags.map(parse_tags).unwrap_or_default();
-    let matches_filter = |disc: &DiscourseConfig| {
-        if filter.is_empty() {
-            return true;
-        }
-        let disc_tags = disc.tags.as_ref().map(|t| {
-            t.iter()
-                .map(|tag| tag.to_ascii_lowercase())
-                .collect::<Vec<_>>()
-        });
-        let Some(disc_tags) = disc_tags else {
-            return false;
-        };
-        filter.iter().any(|tag| {
-            let tag = tag.to_ascii_lowercase();
-            disc_tags.iter().any(|t| t == &tag)
-        })
-    };
 
     let mut matched = 0;
-    for discourse in config.discourse.iter().filter(|d| matches_filter(d)) {
+    for discourse in config.discourse.iter().filter(|d| matches_tags(d, &filter)) {
         matched += 1;
         ensure_api_credentials(discourse)?;
         let client = DiscourseClient::new(discourse)?;
@@ -1,13 +1,15 @@
 use crate::api::DiscourseClient;
 use crate::cli::ListFormat;
-use crate::commands::common::{ensure_api_credentials, select_discourse};
+use crate::commands::common::{ensure_api_credentials, open_url, select_discourse};
 use crate::commands::update::run_ssh_command;
 use crate::config::{Config, DiscourseConfig};
 use crate::utils::slugify;
 use anyhow::{Context, Result, anyhow};
 use serde::Serialize;
 use serde_json::Value;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Debug, Serialize)]
 struct ThemeListEntry {
@@ -31,29 +33,7 @@ pub fn theme_list(
         .and_then(|v| v.as_array())
         .cloned()
         .unwrap_or_default();
-    let entries: Vec<ThemeListEntry> = themes
-        .into_iter()
-        .map(|theme| {
-            let id = theme.get("id").and_then(|v| v.as_u64()).unwrap_or_default();
-            let name = theme
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown")
-                .to_string();
-            let status = theme
-                .get("enabled")
-                .and_then(|v| v.as_bool())
-                .map(|value| {
-                    if value {
-                        "enabled".to_string()
-                    } else {
-                        "disabled".to_string()
-                    }
-                })
-                .unwrap_or_else(|| "unknown".to_string());
-            ThemeListEntry { id, name, status }
-        })
-        .collect();
+    let entries: Vec<ThemeListEntry> = themes.iter().map(theme_list_entry).collect();
 
     match format {
         ListFormat::Text => {
@@ -77,8 +57,55 @@ pub fn theme_list(
     Ok(())
 }
 
-pub fn theme_install(config: &Config, discourse_name: &str, url: &str) -> Result<()> {
+/// Install a theme from a git URL via the admin API (or an SSH template with `ssh`).
+pub fn theme_install(
+    config: &Config,
+    discourse_name: &str,
+    url: &str,
+    branch: Option<&str>,
+    private_key: Option<&Path>,
+    ssh: bool,
+) -> Result<()> {
     let discourse = select_discourse(config, Some(discourse_name))?;
+    if ssh {
+        return theme_install_ssh(discourse, url);
+    }
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let private_key = match private_key {
+        Some(path) => Some(
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?,
+        ),
+        None => None,
+    };
+    let response = client.import_remote_theme(url, branch, private_key.as_deref())?;
+    let theme = response.get("theme").unwrap_or(&response);
+    print_theme_entry(theme);
+    Ok(())
+}
+
+/// Remove a theme by name or ID via the admin API (or an SSH template with `ssh`).
+pub fn theme_remove(config: &Config, discourse_name: &str, name: &str, ssh: bool) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    if ssh {
+        return theme_remove_ssh(discourse, name);
+    }
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let response = client.list_themes()?;
+    let themes = response
+        .get("themes")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let theme = resolve_theme(&themes, name)?;
+    let entry = theme_list_entry(theme);
+    client.delete_theme(entry.id)?;
+    println!("{}", entry.id);
+    Ok(())
+}
+
+fn theme_install_ssh(discourse: &DiscourseConfig, url: &str) -> Result<()> {
     let target = ssh_target(discourse);
     let template = std::env::var("DSC_SSH_THEME_INSTALL_CMD")
         .map_err(|_| {
@@ -95,8 +122,7 @@ pub fn theme_install(config: &Config, discourse_name: &str, url: &str) -> Result
     Ok(())
 }
 
-pub fn theme_remove(config: &Config, discourse_name: &str, name: &str) -> Result<()> {
-    let discourse = select_discourse(config, Some(discourse_name))?;
+fn theme_remove_ssh(discourse: &DiscourseConfig, name: &str) -> Result<()> {
     let target = ssh_target(discourse);
     let template = std::env::var("DSC_SSH_THEME_REMOVE_CMD")
         .map_err(|_| {
@@ -113,12 +139,49 @@ pub fn theme_remove(config: &Config, discourse_name: &str, name: &str) -> Result
     Ok(())
 }
 
-/// Pull a theme to a local JSON file.
+/// Find a theme by numeric ID or by name (case-insensitive).
+fn resolve_theme<'a>(themes: &'a [Value], name_or_id: &str) -> Result<&'a Value> {
+    let needle = name_or_id.trim();
+    if let Ok(id) = needle.parse::<u64>()
+        && let Some(theme) = themes
+            .iter()
+            .find(|theme| theme.get("id").and_then(|v| v.as_u64()) == Some(id))
+    {
+        return Ok(theme);
+    }
+    let matches: Vec<&Value> = themes
+        .iter()
+        .filter(|theme| {
+            theme
+                .get("name")
+                .and_then(|v| v.as_str())
+                .is_some_and(|name| name.trim().eq_ignore_ascii_case(needle))
+        })
+        .collect();
+    match matches.as_slice() {
+        [theme] => Ok(theme),
+        [] => Err(anyhow!("theme not found: {}", name_or_id)),
+        _ => {
+            let ids: Vec<String> = matches
+                .iter()
+                .map(|theme| theme_list_entry(theme).id.to_string())
+                .collect();
+            Err(anyhow!(
+                "multiple themes named {} (IDs {}); pass an ID instead",
+                name_or_id,
+                ids.join(", ")
+            ))
+        }
+    }
+}
+
+/// Pull a theme to a local JSON file, or a directory of source files.
 pub fn theme_pull(
     config: &Config,
     discourse_name: &str,
     theme_id: u64,
     local_path: Option<&Path>,
+    as_dir: bool,
 ) -> Result<()> {
     let discourse = select_discourse(config, Some(discourse_name))?;
     ensure_api_credentials(discourse)?;
@@ -136,13 +199,23 @@ pub fn theme_pull(
                 .and_then(|v| v.as_str())
                 .map(slugify)
                 .unwrap_or_else(|| format!("theme-{}", theme_id));
-            let filename = format!("{}.json", name_slug);
+            let filename = if as_dir {
+                name_slug
+            } else {
+                format!("{}.json", name_slug)
+            };
             std::env::current_dir()
                 .context("getting current directory")?
                 .join(filename)
         }
     };
 
+    if as_dir {
+        write_theme_dir(theme, &path)?;
+        println!("{}", path.display());
+        return Ok(());
+    }
+
     let content =
         serde_json::to_string_pretty(theme).context("serializing theme to JSON")?;
     if let Some(parent) = path.parent() {
@@ -157,34 +230,45 @@ pub fn theme_pull(
     Ok(())
 }
 
-/// Push a local JSON file to create or update a theme.
+/// Push a local JSON file, or a directory of source files, to create or update a theme.
 pub fn theme_push(
     config: &Config,
     discourse_name: &str,
-    json_path: &Path,
+    local_path: &Path,
     theme_id: Option<u64>,
+    as_dir: bool,
 ) -> Result<()> {
     let discourse = select_discourse(config, Some(discourse_name))?;
     ensure_api_credentials(discourse)?;
     let client = DiscourseClient::new(discourse)?;
 
-    let raw = std::fs::read_to_string(json_path)
-        .with_context(|| format!("reading {}", json_path.display()))?;
-    let parsed: Value = serde_json::from_str(&raw)
-        .with_context(|| format!("parsing JSON from {}", json_path.display()))?;
-
-    // Unwrap {"theme": {...}} envelope if present
-    let theme = if let Some(inner) = parsed.get("theme") {
-        inner.clone()
+    let theme = if as_dir {
+        read_theme_dir(local_path)?
     } else {
-        parsed
+        let raw = std::fs::read_to_string(local_path)
+            .with_context(|| format!("reading {}", local_path.display()))?;
+        let parsed: Value = serde_json::from_str(&raw)
+            .with_context(|| format!("parsing JSON from {}", local_path.display()))?;
+
+        // Unwrap {"theme": {...}} envelope if present
+        if let Some(inner) = parsed.get("theme") {
+            inner.clone()
+        } else {
+            parsed
+        }
     };
 
-    let push_data = build_push_payload(&theme);
+    let mut push_data = build_push_payload(&theme);
 
     let target_id = theme_id.or_else(|| theme.get("id").and_then(|v| v.as_u64()));
 
     if let Some(id) = target_id {
+        if as_dir {
+            // Clear remote fields whose source file no longer exists locally.
+            let response = client.fetch_theme(id)?;
+            let remote = response.get("theme").unwrap_or(&response);
+            append_removed_fields(&mut push_data, remote);
+        }
         client.update_theme(id, &push_data)?;
         println!("{}", id);
     } else {
@@ -194,8 +278,10 @@ pub fn theme_push(
             .map(|s| s.trim().is_empty())
             .unwrap_or(true)
         {
+            let source = if as_dir { "about.json" } else { "theme file" };
             return Err(anyhow!(
-                "missing name in theme file; set name or pass a theme ID to update"
+                "missing name in {}; set name or pass a theme ID to update",
+                source
             ));
         }
         let new_id = client.create_theme(&push_data)?;
@@ -234,6 +320,651 @@ pub fn theme_duplicate(
     Ok(())
 }
 
+/// Download a theme bundle to a local file.
+pub fn theme_export(
+    config: &Config,
+    discourse_name: &str,
+    theme_id: u64,
+    local_path: Option<&Path>,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let bundle = client.export_theme(theme_id)?;
+    let extension = bundle_extension(&bundle);
+
+    let path = match local_path {
+        Some(p) => {
+            if !p.to_string_lossy().ends_with(extension) {
+                eprintln!(
+                    "warning: Discourse returned a {} bundle; writing it to {} unchanged",
+                    extension,
+                    p.display()
+                );
+            }
+            p.to_path_buf()
+        }
+        None => {
+            let response = client.fetch_theme(theme_id)?;
+            let theme = response.get("theme").unwrap_or(&response);
+            let name_slug = theme
+                .get("name")
+                .and_then(|v| v.as_str())
+                .map(slugify)
+                .unwrap_or_else(|| format!("theme-{}", theme_id));
+            std::env::current_dir()
+                .context("getting current directory")?
+                .join(format!("{}{}", name_slug, extension))
+        }
+    };
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating {}", parent.display()))?;
+    }
+    std::fs::write(&path, bundle).with_context(|| format!("writing {}", path.display()))?;
+    println!("{}", path.display());
+    Ok(())
+}
+
+/// Import a theme bundle and print the new theme.
+pub fn theme_import(config: &Config, discourse_name: &str, bundle_path: &Path) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let bundle =
+        std::fs::read(bundle_path).with_context(|| format!("reading {}", bundle_path.display()))?;
+    // Discourse picks the archive reader from the file extension, so name
+    // the upload after the actual format rather than the local file name.
+    let stem = bundle_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .map(|name| {
+            [".tar.gz", ".tgz", ".zip"]
+                .iter()
+                .find_map(|ext| name.strip_suffix(ext))
+                .unwrap_or(name)
+                .to_string()
+        })
+        .unwrap_or_else(|| "theme".to_string());
+    let filename = format!("{}{}", stem, bundle_extension(&bundle));
+    let response = client.import_theme_bundle(&filename, bundle, None)?;
+    print_theme_entry(response.get("theme").unwrap_or(&response));
+    Ok(())
+}
+
+/// Copy a theme and its components to another Discourse, updating the
+/// same-named themes there rather than importing duplicates.
+pub fn theme_copy(
+    config: &Config,
+    source: &str,
+    target: Option<&str>,
+    theme_id: u64,
+    target_theme: Option<u64>,
+) -> Result<()> {
+    let source_discourse = select_discourse(config, Some(source))?;
+    let target_discourse = select_discourse(config, Some(target.unwrap_or(source)))?;
+    ensure_api_credentials(source_discourse)?;
+    ensure_api_credentials(target_discourse)?;
+    let source_client = DiscourseClient::new(source_discourse)?;
+    let target_client = DiscourseClient::new(target_discourse)?;
+
+    let source_themes = fetch_themes(&source_client)?;
+    let theme = source_themes
+        .iter()
+        .find(|theme| theme.get("id").and_then(|v| v.as_u64()) == Some(theme_id))
+        .ok_or_else(|| anyhow!("theme not found: {}", theme_id))?;
+    // A copy within one Discourse is a new theme sharing the source's
+    // components, so there is nothing to match by name.
+    let same_discourse = target.is_none_or(|target| target == source);
+    let target_themes = if same_discourse {
+        Vec::new()
+    } else {
+        fetch_themes(&target_client)?
+    };
+
+    let mut child_ids = Vec::new();
+    for child in theme_children(theme) {
+        let child_id = theme_list_entry(&child).id;
+        if same_discourse {
+            child_ids.push(child_id);
+            continue;
+        }
+        let existing = matching_theme(&target_themes, &child)?;
+        let copied = copy_theme_bundle(&source_client, &target_client, child_id, existing)?;
+        print_theme_entry(&copied);
+        child_ids.push(theme_list_entry(&copied).id);
+    }
+
+    let existing = match target_theme {
+        Some(id) => Some(id),
+        None => matching_theme(&target_themes, theme)?,
+    };
+    let copied = copy_theme_bundle(&source_client, &target_client, theme_id, existing)?;
+    if !is_component(theme) {
+        target_client.update_theme(
+            theme_list_entry(&copied).id,
+            &serde_json::json!({ "child_theme_ids": child_ids }),
+        )?;
+    }
+    print_theme_entry(&copied);
+    Ok(())
+}
+
+/// Export one theme and import it on the target, replacing `existing` when set.
+fn copy_theme_bundle(
+    source: &DiscourseClient,
+    target: &DiscourseClient,
+    theme_id: u64,
+    existing: Option<u64>,
+) -> Result<Value> {
+    let bundle = source.export_theme(theme_id)?;
+    let filename = format!("theme-{}{}", theme_id, bundle_extension(&bundle));
+    let response = target.import_theme_bundle(&filename, bundle, existing)?;
+    Ok(response.get("theme").cloned().unwrap_or(response))
+}
+
+fn fetch_themes(client: &DiscourseClient) -> Result<Vec<Value>> {
+    let response = client.list_themes()?;
+    Ok(response
+        .get("themes")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default())
+}
+
+fn is_component(theme: &Value) -> bool {
+    theme
+        .get("component")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// The target theme a copy of `theme` should replace: the one theme (or
+/// component) with the same name, if any.
+fn matching_theme(themes: &[Value], theme: &Value) -> Result<Option<u64>> {
+    let name = theme_list_entry(theme).name;
+    let matches: Vec<u64> = themes
+        .iter()
+        .filter(|candidate| is_component(candidate) == is_component(theme))
+        .filter(|candidate| {
+            candidate
+                .get("name")
+                .and_then(|v| v.as_str())
+                .is_some_and(|candidate| candidate.trim().eq_ignore_ascii_case(name.trim()))
+        })
+        .map(|candidate| theme_list_entry(candidate).id)
+        .collect();
+    match matches.as_slice() {
+        [] => Ok(None),
+        [id] => Ok(Some(*id)),
+        _ => {
+            let ids: Vec<String> = matches.iter().map(|id| id.to_string()).collect();
+            Err(anyhow!(
+                "multiple themes named {} on the target (IDs {}); pass --target-theme or remove the duplicates",
+                name,
+                ids.join(", ")
+            ))
+        }
+    }
+}
+
+/// Pick a file extension from the bundle's magic bytes.
+fn bundle_extension(bundle: &[u8]) -> &'static str {
+    if bundle.starts_with(&[0x1f, 0x8b]) {
+        ".tar.gz"
+    } else {
+        ".zip"
+    }
+}
+
+/// Watch a theme directory and push changed fields whenever files are saved.
+pub fn theme_watch(
+    config: &Config,
+    discourse_name: &str,
+    theme_id: u64,
+    dir: &Path,
+    debounce_ms: u64,
+    open: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    // Fail early on a bad theme ID rather than on the first save.
+    client.fetch_theme(theme_id)?;
+
+    let mut current = snapshot_theme_dir(dir)?;
+    let interval = Duration::from_millis(debounce_ms.max(50));
+    if open {
+        let url = format!(
+            "{}/admin/themes/{}/preview",
+            discourse.baseurl.trim_end_matches('/'),
+            theme_id
+        );
+        open_url(&url)?;
+    }
+    println!(
+        "Watching {} for theme {} (Ctrl-C to stop)",
+        dir.display(),
+        theme_id
+    );
+
+    loop {
+        std::thread::sleep(interval);
+        let mut pending = snapshot_theme_dir(dir)?;
+        if pending == current {
+            continue;
+        }
+        // Debounce: wait until the directory stops changing.
+        loop {
+            std::thread::sleep(interval);
+            let next = snapshot_theme_dir(dir)?;
+            if next == pending {
+                break;
+            }
+            pending = next;
+        }
+
+        let (paths, fields) = changed_theme_fields(&current, &pending);
+        current = pending;
+        if fields.is_empty() {
+            continue;
+        }
+        let payload = serde_json::json!({ "theme_fields": fields });
+        match client.update_theme(theme_id, &payload) {
+            Ok(response) => {
+                println!("Pushed {}", paths.join(", "));
+                let theme = response.get("theme").unwrap_or(&response);
+                for error in theme_field_errors(theme) {
+                    eprintln!("{}", error);
+                }
+            }
+            Err(err) => eprintln!("Push failed: {:#}", err),
+        }
+    }
+}
+
+/// Read every theme field source file under `dir`, keyed by relative path.
+fn snapshot_theme_dir(dir: &Path) -> Result<BTreeMap<String, String>> {
+    if !dir.is_dir() {
+        return Err(anyhow!("theme directory not found: {}", dir.display()));
+    }
+    let mut files = Vec::new();
+    collect_theme_files(dir, dir, &mut files)?;
+    let mut out = BTreeMap::new();
+    for rel in files {
+        if theme_field_from_path(&rel).is_none() {
+            continue;
+        }
+        // Editors may replace files mid-scan; the next poll picks them up.
+        if let Ok(value) = std::fs::read_to_string(dir.join(&rel)) {
+            out.insert(rel, value);
+        }
+    }
+    Ok(out)
+}
+
+/// Diff two directory snapshots into changed paths and theme field payloads.
+/// Deleted files become empty values so Discourse removes the field.
+fn changed_theme_fields(
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+) -> (Vec<String>, Vec<Value>) {
+    let mut paths = Vec::new();
+    let mut fields = Vec::new();
+    for (rel, value) in after {
+        if before.get(rel) == Some(value) {
+            continue;
+        }
+        if let Some(key) = theme_field_from_path(rel) {
+            paths.push(rel.clone());
+            fields.push(theme_field_value(&key, value.clone()));
+        }
+    }
+    for rel in before.keys() {
+        if after.contains_key(rel) {
+            continue;
+        }
+        if let Some(key) = theme_field_from_path(rel) {
+            paths.push(format!("{} (removed)", rel));
+            fields.push(theme_field_value(&key, String::new()));
+        }
+    }
+    (paths, fields)
+}
+
+/// Collect compile errors reported on theme fields, labelled by source path.
+fn theme_field_errors(theme: &Value) -> Vec<String> {
+    let fields = theme
+        .get("theme_fields")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    fields
+        .iter()
+        .filter_map(|field| {
+            let error = field.get("error").and_then(|v| v.as_str())?.trim();
+            if error.is_empty() {
+                return None;
+            }
+            let target = field.get("target").and_then(|v| v.as_str()).unwrap_or("");
+            let name = field.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            let label = theme_field_path(target, name)
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| format!("{}/{}", target, name));
+            Some(format!("{}: {}", label, error))
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+struct ThemeSettingEntry {
+    setting: String,
+    value: String,
+}
+
+/// List all settings for a theme.
+pub fn theme_settings_list(
+    config: &Config,
+    discourse_name: &str,
+    theme_id: u64,
+    format: ListFormat,
+    verbose: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let response = client.fetch_theme(theme_id)?;
+    let theme = response.get("theme").unwrap_or(&response);
+    let entries: Vec<ThemeSettingEntry> = theme_settings(theme)
+        .iter()
+        .filter_map(|setting| {
+            let name = setting.get("setting").and_then(|v| v.as_str())?;
+            Some(ThemeSettingEntry {
+                setting: name.to_string(),
+                value: theme_setting_value(setting),
+            })
+        })
+        .collect();
+
+    match format {
+        ListFormat::Text => {
+            if entries.is_empty() && !verbose {
+                println!("No settings found.");
+                return Ok(());
+            }
+            for e in &entries {
+                println!("{} = {}", e.setting, e.value);
+            }
+        }
+        ListFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        }
+        ListFormat::Yaml => {
+            println!("{}", serde_yaml::to_string(&entries)?);
+        }
+    }
+    Ok(())
+}
+
+/// Print the current value of a theme setting.
+pub fn theme_settings_get(
+    config: &Config,
+    discourse_name: &str,
+    theme_id: u64,
+    setting: &str,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let response = client.fetch_theme(theme_id)?;
+    let theme = response.get("theme").unwrap_or(&response);
+    let entry = find_theme_setting(theme, setting)
+        .ok_or_else(|| anyhow!("theme {} has no setting named {}", theme_id, setting))?;
+    println!("{}", theme_setting_value(&entry));
+    Ok(())
+}
+
+/// Update a theme setting and print the theme.
+pub fn theme_settings_set(
+    config: &Config,
+    discourse_name: &str,
+    theme_id: u64,
+    setting: &str,
+    value: &str,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let response = client.fetch_theme(theme_id)?;
+    let theme = response.get("theme").unwrap_or(&response);
+    if find_theme_setting(theme, setting).is_none() {
+        return Err(anyhow!(
+            "theme {} has no setting named {}",
+            theme_id,
+            setting
+        ));
+    }
+    client.update_theme_setting(theme_id, setting, value)?;
+    print_theme_entry(theme);
+    Ok(())
+}
+
+/// Add a component to a parent theme and print the parent with its components.
+pub fn theme_component_add(
+    config: &Config,
+    discourse_name: &str,
+    parent_id: u64,
+    child_id: u64,
+) -> Result<()> {
+    update_theme_components(config, discourse_name, parent_id, child_id, true)
+}
+
+/// Remove a component from a parent theme and print the parent with its components.
+pub fn theme_component_remove(
+    config: &Config,
+    discourse_name: &str,
+    parent_id: u64,
+    child_id: u64,
+) -> Result<()> {
+    update_theme_components(config, discourse_name, parent_id, child_id, false)
+}
+
+fn update_theme_components(
+    config: &Config,
+    discourse_name: &str,
+    parent_id: u64,
+    child_id: u64,
+    add: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let response = client.fetch_theme(parent_id)?;
+    let parent = response.get("theme").unwrap_or(&response);
+    let mut child_ids: Vec<u64> = theme_children(parent)
+        .iter()
+        .filter_map(|child| child.get("id").and_then(|v| v.as_u64()))
+        .collect();
+    let present = child_ids.contains(&child_id);
+    if add {
+        if present {
+            return Err(anyhow!(
+                "theme {} is already a component of theme {}",
+                child_id,
+                parent_id
+            ));
+        }
+        child_ids.push(child_id);
+    } else {
+        if !present {
+            return Err(anyhow!(
+                "theme {} is not a component of theme {}",
+                child_id,
+                parent_id
+            ));
+        }
+        child_ids.retain(|id| *id != child_id);
+    }
+
+    let updated = client.update_theme(
+        parent_id,
+        &serde_json::json!({ "child_theme_ids": child_ids }),
+    )?;
+    let updated = updated.get("theme").unwrap_or(&updated);
+    let parent = if updated.get("id").is_some() {
+        updated
+    } else {
+        parent
+    };
+    print_theme_entry(parent);
+    for child in theme_children(parent) {
+        let entry = theme_list_entry(&child);
+        println!("  {} - {} - {}", entry.id, entry.name, entry.status);
+    }
+    Ok(())
+}
+
+/// Make a theme the site default and print it.
+pub fn theme_set_default(config: &Config, discourse_name: &str, theme_id: u64) -> Result<()> {
+    update_theme_flags(
+        config,
+        discourse_name,
+        theme_id,
+        serde_json::json!({ "default": true }),
+    )
+}
+
+/// Enable or disable a theme and print it.
+pub fn theme_set_enabled(
+    config: &Config,
+    discourse_name: &str,
+    theme_id: u64,
+    enabled: bool,
+) -> Result<()> {
+    update_theme_flags(
+        config,
+        discourse_name,
+        theme_id,
+        serde_json::json!({ "enabled": enabled }),
+    )
+}
+
+/// Set whether users can select a theme and print it.
+pub fn theme_set_user_selectable(
+    config: &Config,
+    discourse_name: &str,
+    theme_id: u64,
+    selectable: bool,
+) -> Result<()> {
+    update_theme_flags(
+        config,
+        discourse_name,
+        theme_id,
+        serde_json::json!({ "user_selectable": selectable }),
+    )
+}
+
+/// Pull the latest commit of a remote theme and print it.
+pub fn theme_update_remote(config: &Config, discourse_name: &str, theme_id: u64) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let response = client.fetch_theme(theme_id)?;
+    let theme = response.get("theme").unwrap_or(&response);
+    if theme.get("remote_theme").is_none_or(|v| v.is_null()) {
+        return Err(anyhow!(
+            "theme {} was not installed from a remote repository",
+            theme_id
+        ));
+    }
+    let updated = client.update_theme(theme_id, &serde_json::json!({ "remote_update": true }))?;
+    let updated = updated.get("theme").unwrap_or(&updated);
+    print_theme_entry(if updated.get("id").is_some() {
+        updated
+    } else {
+        theme
+    });
+    Ok(())
+}
+
+fn update_theme_flags(
+    config: &Config,
+    discourse_name: &str,
+    theme_id: u64,
+    payload: Value,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let updated = client.update_theme(theme_id, &payload)?;
+    let updated = updated.get("theme").unwrap_or(&updated);
+    if updated.get("id").is_some() {
+        print_theme_entry(updated);
+    } else {
+        let response = client.fetch_theme(theme_id)?;
+        print_theme_entry(response.get("theme").unwrap_or(&response));
+    }
+    Ok(())
+}
+
+fn theme_list_entry(theme: &Value) -> ThemeListEntry {
+    let id = theme.get("id").and_then(|v| v.as_u64()).unwrap_or_default();
+    let name = theme
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+    let status = theme
+        .get("enabled")
+        .and_then(|v| v.as_bool())
+        .map(|value| {
+            if value {
+                "enabled".to_string()
+            } else {
+                "disabled".to_string()
+            }
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+    ThemeListEntry { id, name, status }
+}
+
+fn print_theme_entry(theme: &Value) {
+    let entry = theme_list_entry(theme);
+    println!("{} - {} - {}", entry.id, entry.name, entry.status);
+}
+
+fn theme_children(theme: &Value) -> Vec<Value> {
+    theme
+        .get("child_themes")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn theme_settings(theme: &Value) -> Vec<Value> {
+    theme
+        .get("settings")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn find_theme_setting(theme: &Value, name: &str) -> Option<Value> {
+    theme_settings(theme)
+        .into_iter()
+        .find(|setting| setting.get("setting").and_then(|v| v.as_str()) == Some(name))
+}
+
+fn theme_setting_value(setting: &Value) -> String {
+    match setting.get("value") {
+        Some(Value::String(value)) => value.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(value) => value.to_string(),
+    }
+}
+
 /// Build a payload suitable for creating or updating a theme.
 /// Strips server-generated and read-only fields.
 fn build_push_payload(theme: &Value) -> Value {
@@ -253,6 +984,281 @@ fn build_push_payload(theme: &Value) -> Value {
     Value::Object(map)
 }
 
+/// HTML fields a theme can define for the common/desktop/mobile targets.
+const THEME_HTML_FIELDS: &[&str] = &[
+    "head_tag",
+    "header",
+    "after_header",
+    "body_tag",
+    "footer",
+    "embedded_header",
+];
+
+// ThemeField type IDs as used by Discourse.
+const FIELD_TYPE_HTML: u64 = 0;
+const FIELD_TYPE_SCSS: u64 = 1;
+const FIELD_TYPE_UPLOAD: u64 = 2;
+const FIELD_TYPE_YAML: u64 = 5;
+const FIELD_TYPE_JS: u64 = 6;
+
+/// A theme field addressed by its target and name.
+#[derive(Debug, Clone, PartialEq)]
+struct ThemeFieldKey {
+    target: String,
+    name: String,
+    type_id: u64,
+}
+
+impl ThemeFieldKey {
+    fn new(target: &str, name: &str, type_id: u64) -> Self {
+        Self {
+            target: target.to_string(),
+            name: name.to_string(),
+            type_id,
+        }
+    }
+}
+
+/// Map a theme field to its path in the standard theme directory layout.
+fn theme_field_path(target: &str, name: &str) -> Option<PathBuf> {
+    if name.is_empty()
+        || name.starts_with('/')
+        || name.contains('\\')
+        || name.split('/').any(|part| part == ".." || part == ".")
+    {
+        return None;
+    }
+    let rel = match target {
+        "common" | "desktop" | "mobile" => match name {
+            "scss" => format!("{target}/{target}.scss"),
+            "embedded_scss" => format!("{target}/embedded.scss"),
+            "color_definitions" => format!("{target}/color_definitions.scss"),
+            _ if THEME_HTML_FIELDS.contains(&name) => format!("{target}/{name}.html"),
+            _ => return None,
+        },
+        "settings" if name == "yaml" => "settings.yml".to_string(),
+        "translations" if !name.contains('/') => format!("locales/{name}.yml"),
+        "extra_js" => format!("javascripts/{name}"),
+        "extra_scss" => format!("stylesheets/{name}.scss"),
+        "tests_js" => format!("test/{name}"),
+        _ => return None,
+    };
+    Some(PathBuf::from(rel))
+}
+
+/// Map a path relative to a theme directory back to its theme field.
+fn theme_field_from_path(rel: &str) -> Option<ThemeFieldKey> {
+    if rel == "settings.yml" {
+        return Some(ThemeFieldKey::new("settings", "yaml", FIELD_TYPE_YAML));
+    }
+    if let Some(locale) = rel
+        .strip_prefix("locales/")
+        .and_then(|rest| rest.strip_suffix(".yml"))
+    {
+        if locale.is_empty() || locale.contains('/') {
+            return None;
+        }
+        return Some(ThemeFieldKey::new("translations", locale, FIELD_TYPE_YAML));
+    }
+    if let Some(name) = rel.strip_prefix("javascripts/") {
+        return Some(ThemeFieldKey::new("extra_js", name, FIELD_TYPE_JS));
+    }
+    if let Some(name) = rel
+        .strip_prefix("stylesheets/")
+        .and_then(|rest| rest.strip_suffix(".scss"))
+    {
+        return Some(ThemeFieldKey::new("extra_scss", name, FIELD_TYPE_SCSS));
+    }
+    if let Some(name) = rel.strip_prefix("test/") {
+        return Some(ThemeFieldKey::new("tests_js", name, FIELD_TYPE_JS));
+    }
+    let (target, file) = rel.split_once('/')?;
+    if !matches!(target, "common" | "desktop" | "mobile") || file.contains('/') {
+        return None;
+    }
+    if file == format!("{target}.scss") {
+        return Some(ThemeFieldKey::new(target, "scss", FIELD_TYPE_SCSS));
+    }
+    match file {
+        "embedded.scss" => Some(ThemeFieldKey::new(target, "embedded_scss", FIELD_TYPE_SCSS)),
+        "color_definitions.scss" => Some(ThemeFieldKey::new(
+            target,
+            "color_definitions",
+            FIELD_TYPE_SCSS,
+        )),
+        _ => {
+            let name = file.strip_suffix(".html")?;
+            THEME_HTML_FIELDS
+                .contains(&name)
+                .then(|| ThemeFieldKey::new(target, name, FIELD_TYPE_HTML))
+        }
+    }
+}
+
+/// Write a theme as about.json plus one source file per theme field.
+fn write_theme_dir(theme: &Value, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+
+    let mut about = serde_json::Map::new();
+    for key in ["name", "component"] {
+        if let Some(val) = theme.get(key) {
+            about.insert(key.to_string(), val.clone());
+        }
+    }
+    if let Some(remote) = theme.get("remote_theme") {
+        for key in ["about_url", "license_url"] {
+            if let Some(val) = remote.get(key).filter(|v| !v.is_null()) {
+                about.insert(key.to_string(), val.clone());
+            }
+        }
+    }
+    let about_path = dir.join("about.json");
+    let content =
+        serde_json::to_string_pretty(&Value::Object(about)).context("serializing about.json")?;
+    std::fs::write(&about_path, content)
+        .with_context(|| format!("writing {}", about_path.display()))?;
+
+    let fields = theme
+        .get("theme_fields")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for field in &fields {
+        let target = field.get("target").and_then(|v| v.as_str()).unwrap_or("");
+        let name = field.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        let type_id = field.get("type_id").and_then(|v| v.as_u64());
+        if type_id == Some(FIELD_TYPE_UPLOAD) {
+            eprintln!("Skipping uploaded asset {}/{}", target, name);
+            continue;
+        }
+        let Some(rel) = theme_field_path(target, name) else {
+            eprintln!("Skipping unsupported theme field {}/{}", target, name);
+            continue;
+        };
+        let value = field.get("value").and_then(|v| v.as_str()).unwrap_or("");
+        let path = dir.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("creating {}", parent.display()))?;
+        }
+        std::fs::write(&path, value).with_context(|| format!("writing {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Assemble a theme payload from a directory in the standard theme layout.
+fn read_theme_dir(dir: &Path) -> Result<Value> {
+    if !dir.is_dir() {
+        return Err(anyhow!("theme directory not found: {}", dir.display()));
+    }
+    let mut theme = serde_json::Map::new();
+    let about_path = dir.join("about.json");
+    if about_path.exists() {
+        let raw = std::fs::read_to_string(&about_path)
+            .with_context(|| format!("reading {}", about_path.display()))?;
+        let about: Value = serde_json::from_str(&raw)
+            .with_context(|| format!("parsing JSON from {}", about_path.display()))?;
+        for key in ["name", "component"] {
+            if let Some(val) = about.get(key) {
+                theme.insert(key.to_string(), val.clone());
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    collect_theme_files(dir, dir, &mut files)?;
+    let mut fields = Vec::new();
+    for rel in files {
+        let Some(key) = theme_field_from_path(&rel) else {
+            continue;
+        };
+        let path = dir.join(&rel);
+        let value = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        fields.push(theme_field_value(&key, value));
+    }
+    theme.insert("theme_fields".to_string(), Value::Array(fields));
+    Ok(Value::Object(theme))
+}
+
+/// Recursively list files under `dir` as `/`-separated paths relative to `base`.
+/// Hidden entries and node_modules are skipped.
+fn collect_theme_files(base: &Path, dir: &Path, out: &mut Vec<String>) -> Result<()> {
+    let mut entries = std::fs::read_dir(dir)
+        .with_context(|| format!("reading {}", dir.display()))?
+        .collect::<std::io::Result<Vec<_>>>()
+        .with_context(|| format!("reading {}", dir.display()))?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        if file_name.starts_with('.') || file_name == "node_modules" {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            collect_theme_files(base, &path, out)?;
+        } else if let Ok(rel) = path.strip_prefix(base) {
+            let parts: Vec<String> = rel
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            out.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+fn theme_field_value(key: &ThemeFieldKey, value: String) -> Value {
+    serde_json::json!({
+        "target": key.target,
+        "name": key.name,
+        "type_id": key.type_id,
+        "value": value,
+    })
+}
+
+/// Add empty values for remote fields with no local source file, which
+/// makes Discourse delete them. Uploaded assets are left alone.
+fn append_removed_fields(push_data: &mut Value, remote: &Value) {
+    let Some(local) = push_data
+        .get_mut("theme_fields")
+        .and_then(|v| v.as_array_mut())
+    else {
+        return;
+    };
+    let present: Vec<(String, String)> = local
+        .iter()
+        .map(|field| {
+            (
+                field["target"].as_str().unwrap_or("").to_string(),
+                field["name"].as_str().unwrap_or("").to_string(),
+            )
+        })
+        .collect();
+    let remote_fields = remote
+        .get("theme_fields")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for field in remote_fields {
+        let target = field.get("target").and_then(|v| v.as_str()).unwrap_or("");
+        let name = field.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        let type_id = field.get("type_id").and_then(|v| v.as_u64());
+        if type_id == Some(FIELD_TYPE_UPLOAD) || theme_field_path(target, name).is_none() {
+            continue;
+        }
+        if present
+            .iter()
+            .any(|(t, n)| t.as_str() == target && n.as_str() == name)
+        {
+            continue;
+        }
+        let key = ThemeFieldKey::new(target, name, type_id.unwrap_or_default());
+        local.push(theme_field_value(&key, String::new()));
+    }
+}
+
 fn ssh_target(discourse: &DiscourseConfig) -> String {
     discourse
         .ssh_host
@@ -267,3 +1273,100 @@ fn render_template(template: &str, replacements: &[(&str, &str)]) -> String {
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        changed_theme_fields, matching_theme, resolve_theme, theme_field_from_path,
+        theme_field_path,
+    };
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    #[test]
+    fn theme_field_paths_round_trip() {
+        let cases = [
+            ("common", "scss", "common/common.scss"),
+            ("desktop", "head_tag", "desktop/head_tag.html"),
+            ("mobile", "embedded_scss", "mobile/embedded.scss"),
+            ("settings", "yaml", "settings.yml"),
+            ("translations", "en", "locales/en.yml"),
+            (
+                "extra_js",
+                "discourse/api-initializers/init.gjs",
+                "javascripts/discourse/api-initializers/init.gjs",
+            ),
+            ("extra_scss", "nested/vars", "stylesheets/nested/vars.scss"),
+        ];
+        for (target, name, rel) in cases {
+            assert_eq!(
+                theme_field_path(target, name),
+                Some(PathBuf::from(rel)),
+                "{target}/{name}"
+            );
+            let key = theme_field_from_path(rel).expect(rel);
+            assert_eq!((key.target.as_str(), key.name.as_str()), (target, name));
+        }
+    }
+
+    #[test]
+    fn changed_theme_fields_reports_edits_and_removals() {
+        let before = BTreeMap::from([
+            ("common/common.scss".to_string(), "a {}".to_string()),
+            ("settings.yml".to_string(), "x: 1".to_string()),
+            ("mobile/footer.html".to_string(), "<p>".to_string()),
+        ]);
+        let after = BTreeMap::from([
+            ("common/common.scss".to_string(), "b {}".to_string()),
+            ("settings.yml".to_string(), "x: 1".to_string()),
+        ]);
+        let (paths, fields) = changed_theme_fields(&before, &after);
+        assert_eq!(
+            paths,
+            vec!["common/common.scss", "mobile/footer.html (removed)"]
+        );
+        assert_eq!(fields[0]["value"], "b {}");
+        assert_eq!(fields[1]["target"], "mobile");
+        assert_eq!(fields[1]["value"], "");
+    }
+
+    #[test]
+    fn resolve_theme_matches_id_then_unique_name() {
+        let themes = vec![
+            serde_json::json!({ "id": 3, "name": "Light" }),
+            serde_json::json!({ "id": 4, "name": "Dark" }),
+            serde_json::json!({ "id": 5, "name": "dark" }),
+        ];
+        assert_eq!(resolve_theme(&themes, "4").unwrap()["id"], 4);
+        assert_eq!(resolve_theme(&themes, "light").unwrap()["id"], 3);
+        assert!(resolve_theme(&themes, "Dark").is_err());
+        assert!(resolve_theme(&themes, "Missing").is_err());
+    }
+
+    #[test]
+    fn matching_theme_pairs_same_name_and_kind() {
+        let themes = vec![
+            serde_json::json!({ "id": 3, "name": "Light", "component": false }),
+            serde_json::json!({ "id": 4, "name": "Header", "component": true }),
+            serde_json::json!({ "id": 5, "name": "Header", "component": false }),
+            serde_json::json!({ "id": 6, "name": "Footer", "component": true }),
+            serde_json::json!({ "id": 7, "name": "footer", "component": true }),
+        ];
+        let component = |name: &str| serde_json::json!({ "name": name, "component": true });
+        assert_eq!(
+            matching_theme(&themes, &component("Header")).unwrap(),
+            Some(4)
+        );
+        assert_eq!(matching_theme(&themes, &component("Light")).unwrap(), None);
+        assert_eq!(matching_theme(&[], &component("Header")).unwrap(), None);
+        assert!(matching_theme(&themes, &component("Footer")).is_err());
+    }
+
+    #[test]
+    fn theme_field_paths_reject_unknown_and_escaping_names() {
+        assert_eq!(theme_field_path("common", "unknown"), None);
+        assert_eq!(theme_field_path("extra_js", "../outside.js"), None);
+        assert_eq!(theme_field_from_path("README.md"), None);
+        assert_eq!(theme_field_from_path("common/other.scss"), None);
+    }
+}
@@ -1,83 +1,987 @@
 use crate::api::{DiscourseClient, VersionInfo};
-use crate::commands::common::{ensure_api_credentials, missing_config};
-use crate::config::{Config, DiscourseConfig, find_discourse};
+use crate::commands::backup::{backup_timeout, create_backup_and_wait};
+use crate::commands::common::{ensure_api_credentials, matches_tags, missing_config, parse_tags};
+use crate::commands::notify::{NotifyEvent, NotifyKind, NotifyStage, notify, notify_hooks};
+use crate::commands::update_state::{
+    InstallStatus, RunTracker, StageLog, UpdateRun, UpdateStage, list_runs, load_run, run_log_dir,
+    update_state_dir,
+};
+use crate::commands::update_template::{TemplateVars, render_template};
+use crate::config::{
+    ChangelogConfig, Config, DiscourseConfig, SmokeChecks, UpdateRecipe, UpdateStep,
+    UpdateStepKind, find_discourse,
+};
 use crate::utils::color_discourse_label;
 use anyhow::{Context, Result, anyhow};
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::io::{self, Write};
 use std::io::{BufRead, BufReader, IsTerminal};
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::mpsc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 const DEFAULT_PARALLEL_UPDATE_WORKERS: usize = 3;
 
-pub fn update_one(config: &Config, name: &str, post_changelog: bool, yes: bool) -> Result<()> {
-    let discourse =
-        find_discourse(config, name).ok_or_else(|| anyhow!("discourse not found: {}", name))?;
-    let metadata = run_update(discourse)?;
-    let payload = print_update_summary(discourse, &metadata);
-    if post_changelog {
-        handle_changelog_post(discourse, &payload, yes)?;
+/// Flags shared by `dsc update <name>` and `dsc update all`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UpdateOptions {
+    pub post_changelog: bool,
+    pub yes: bool,
+    pub backup_first: bool,
+    /// Rebuild web-only containers by bootstrapping first, then destroy/start.
+    pub zero_downtime: bool,
+    /// Check the version and homepage after updating; a failure marks the install failed.
+    pub health_check: bool,
+}
+
+/// Overall result of `dsc update all`, mapped to the process exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateRunStatus {
+    AllOk,
+    SomeFailed,
+    AllFailed,
+}
+
+impl UpdateRunStatus {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            UpdateRunStatus::AllOk => 0,
+            UpdateRunStatus::SomeFailed => 2,
+            UpdateRunStatus::AllFailed => 3,
+        }
+    }
+
+    fn from_entries(entries: &[UpdateReportEntry]) -> Self {
+        let failed = entries.iter().filter(|entry| entry.error.is_some()).count();
+        if failed == 0 {
+            UpdateRunStatus::AllOk
+        } else if failed < entries.len() {
+            UpdateRunStatus::SomeFailed
+        } else {
+            UpdateRunStatus::AllFailed
+        }
+    }
+}
+
+pub fn update_one(config: &Config, name: &str, options: UpdateOptions) -> Result<()> {
+    let discourse = &update_target(config, name)?;
+    let tracker = start_run(std::slice::from_ref(discourse), options, None)?;
+    tracker.record(&discourse.name, |install| {
+        install.status = InstallStatus::Running
+    });
+    let log_dir = tracker.log_dir(&discourse.name);
+    notify_update(discourse, &tracker, NotifyStage::Started, None, None);
+    let mut after_version = None;
+    let result = run_update(discourse, options, &tracker).and_then(|metadata| {
+        after_version = metadata.after_version.clone();
+        let changelog = print_update_summary(discourse, &metadata, &log_dir);
+        if options.post_changelog {
+            post_changelog_once(discourse, &changelog, options.yes, &tracker)?;
+        }
+        match metadata.smoke_failure {
+            Some(failure) => Err(anyhow!("smoke checks failed: {}", failure)),
+            None => Ok(()),
+        }
+    });
+    let error = result.as_ref().err().map(|err| format!("{:#}", err));
+    record_outcome(&tracker, &discourse.name, error.clone());
+    let stage = if error.is_some() {
+        NotifyStage::Failed
+    } else {
+        NotifyStage::Finished
+    };
+    notify_update(discourse, &tracker, stage, after_version, error);
+    tracker.finish();
+    if result.is_err() {
+        eprintln!("Logs: {}", log_dir.display());
+        print_resume_hint(&tracker);
+    }
+    result
+}
+
+/// Print recorded update runs, then the per-discourse stages of one run
+/// (the latest unless `run_id` is given).
+pub fn update_status(run_id: Option<&str>) -> Result<()> {
+    let runs = list_runs()?;
+    let Some(latest) = runs.last() else {
+        println!(
+            "No update runs recorded in {}",
+            update_state_dir().display()
+        );
+        return Ok(());
+    };
+    let rows: Vec<[String; 6]> = runs
+        .iter()
+        .map(|run| {
+            [
+                run.id.clone(),
+                run.started_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+                update_run_state(run).to_string(),
+                run.count(InstallStatus::Ok).to_string(),
+                run.count(InstallStatus::Failed).to_string(),
+                (run.count(InstallStatus::Pending) + run.count(InstallStatus::Running)).to_string(),
+            ]
+        })
+        .collect();
+    print!(
+        "{}",
+        align_columns(
+            ["Run", "Started", "State", "Ok", "Failed", "Pending"],
+            &rows
+        )
+    );
+
+    let run = match run_id {
+        Some(id) => load_run(id)?,
+        None => latest.clone(),
+    };
+    println!("\nRun {} ({}):", run.id, update_run_state(&run));
+    let rows: Vec<[String; 3]> = run
+        .installs
+        .iter()
+        .map(|install| {
+            let stages = if install.stages.is_empty() {
+                "-".to_string()
+            } else {
+                install
+                    .stages
+                    .iter()
+                    .map(|stage| stage.label())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            [
+                install.name.clone(),
+                install.status.label().to_string(),
+                stages,
+            ]
+        })
+        .collect();
+    print!(
+        "{}",
+        align_columns(["Name", "Status", "Completed stages"], &rows)
+    );
+    for install in &run.installs {
+        if let Some(error) = install.error.as_deref() {
+            println!("{}: {}", install.name, error);
+        }
     }
     Ok(())
 }
 
-pub fn update_all(
+/// Print the stage logs one discourse wrote in a run (the latest run that
+/// included it unless `run_id` is given).
+pub fn update_logs(name: &str, run_id: Option<&str>) -> Result<()> {
+    let run = match run_id {
+        Some(id) => load_run(id)?,
+        None => list_runs()?
+            .into_iter()
+            .rev()
+            .find(|run| run.installs.iter().any(|install| install.name == name))
+            .ok_or_else(|| anyhow!("no update run includes {}", name))?,
+    };
+    let dir = run_log_dir(&run.id, name);
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("log"))
+                .collect()
+        })
+        .unwrap_or_default();
+    if files.is_empty() {
+        return Err(anyhow!(
+            "no logs recorded for {} in update run {}",
+            name,
+            run.id
+        ));
+    }
+    files.sort();
+    eprintln!("Update run {}: {}", run.id, dir.display());
+    for path in files {
+        let raw = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        println!("==> {} <==", file_name);
+        print!("{}", raw);
+    }
+    Ok(())
+}
+
+fn update_run_state(run: &UpdateRun) -> &'static str {
+    if run.finished_at.is_none() {
+        "in progress"
+    } else if run.unfinished().is_empty() {
+        "finished"
+    } else {
+        "failed"
+    }
+}
+
+/// Continue an interrupted or partly failed run from each install's last
+/// completed stage, with the changelog/backup/rollout settings it was started with.
+pub fn update_resume(
     config: &Config,
+    run_id: Option<&str>,
     parallel: bool,
     max: Option<usize>,
-    post_changelog: bool,
     yes: bool,
-) -> Result<()> {
-    if !parallel {
-        for discourse in &config.discourse {
-            let metadata = run_update(discourse)?;
-            let payload = print_update_summary(discourse, &metadata);
-            if post_changelog {
-                handle_changelog_post(discourse, &payload, yes)?;
+    report: Option<&Path>,
+) -> Result<UpdateRunStatus> {
+    let tracker = RunTracker::resume(run_id)?;
+    let run = tracker.snapshot();
+    let targets = run
+        .unfinished()
+        .iter()
+        .map(|name| {
+            find_discourse(config, name)
+                .ok_or_else(|| anyhow!("discourse not found: {}", name))
+                .and_then(|discourse| with_update_settings(config, discourse))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    eprintln!(
+        "Resuming update run {} ({} install(s) left)",
+        run.id,
+        targets.len()
+    );
+    let options = UpdateOptions {
+        post_changelog: run.post_changelog,
+        yes,
+        backup_first: run.backup_first,
+        zero_downtime: run.zero_downtime,
+        health_check: run.health_check,
+    };
+    run_fleet(
+        &targets, parallel, max, options, run.waves, report, &tracker,
+    )
+}
+
+fn start_run(
+    targets: &[DiscourseConfig],
+    options: UpdateOptions,
+    waves: Option<WaveOptions>,
+) -> Result<RunTracker> {
+    let names: Vec<String> = targets.iter().map(|d| d.name.clone()).collect();
+    let tracker = RunTracker::start(
+        &names,
+        options.post_changelog,
+        options.backup_first,
+        options.zero_downtime,
+        options.health_check,
+        waves,
+    )?;
+    if !names.is_empty() {
+        eprintln!(
+            "Update run {} (state: {})",
+            tracker.snapshot().id,
+            tracker.path().display()
+        );
+    }
+    Ok(tracker)
+}
+
+fn print_resume_hint(tracker: &RunTracker) {
+    eprintln!(
+        "Resume with: dsc update --resume --run {}",
+        tracker.snapshot().id
+    );
+}
+
+fn record_outcome(tracker: &RunTracker, name: &str, error: Option<String>) {
+    tracker.record(name, |install| {
+        install.status = if error.is_some() {
+            InstallStatus::Failed
+        } else {
+            InstallStatus::Ok
+        };
+        install.error = error;
+    });
+}
+
+/// Look up one discourse by name, with its update settings applied.
+pub fn update_target(config: &Config, name: &str) -> Result<DiscourseConfig> {
+    let discourse =
+        find_discourse(config, name).ok_or_else(|| anyhow!("discourse not found: {}", name))?;
+    with_update_settings(config, discourse)
+}
+
+/// Copy of `discourse` with its effective update recipe in `update` (the
+/// inline `[discourse.update]` table, else the named `update_recipe`) and its
+/// changelog settings in `changelog` (its own fields over the global ones),
+/// and its webhooks in `notify`.
+fn with_update_settings(config: &Config, discourse: &DiscourseConfig) -> Result<DiscourseConfig> {
+    let hooks = notify_hooks(config, discourse).to_vec();
+    let mut discourse = discourse.clone();
+    discourse.notify = Some(hooks);
+    if let Some(global) = config.changelog.as_ref() {
+        let own = discourse.changelog.take().unwrap_or_default();
+        discourse.changelog = Some(ChangelogConfig {
+            template: own.template.or_else(|| global.template.clone()),
+            category_id: own.category_id.or(global.category_id),
+            title: own.title.or_else(|| global.title.clone()),
+        });
+    }
+    if discourse.update.is_none()
+        && let Some(name) = discourse.update_recipe.as_deref()
+    {
+        let recipe = config
+            .update_recipes
+            .get(name)
+            .ok_or_else(|| anyhow!("update recipe not found: {}", name))?;
+        discourse.update = Some(recipe.clone());
+    }
+    if let Some(steps) = discourse
+        .update
+        .as_ref()
+        .and_then(|recipe| recipe.steps.as_ref())
+    {
+        let mut stages = Vec::new();
+        for step in steps {
+            if step.step == UpdateStepKind::Command
+                && (step.name.is_none() || step.command.is_none())
+            {
+                return Err(anyhow!(
+                    "update recipe for {}: command steps need a name and a command",
+                    discourse.name
+                ));
+            }
+            let stage = update_step_stage(step);
+            if stages.contains(&stage) {
+                return Err(anyhow!(
+                    "update recipe for {}: duplicate step '{}'",
+                    discourse.name,
+                    update_step_label(step)
+                ));
             }
+            stages.push(stage);
         }
-        return Ok(());
     }
+    Ok(discourse)
+}
 
-    let max_threads = parallel_worker_count(max, config.discourse.len());
-    let mut handles: Vec<thread::JoinHandle<Result<()>>> = Vec::new();
-    for discourse in config.discourse.clone() {
-        if handles.len() >= max_threads {
-            if let Some(handle) = handles.pop() {
-                handle.join().expect("thread panicked")?;
-            }
+/// Canary/batch rollout settings for `dsc update --waves`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WaveOptions {
+    /// Installs updated per batch after the canary.
+    pub batch_size: usize,
+    /// Stop once more than this percentage of finished installs have failed.
+    pub max_failure_rate: f64,
+}
+
+/// Pick the installs for a fleet update: tag filter, minus exclusions, with
+/// the canary (if any) moved to the front.
+pub fn select_update_targets(
+    config: &Config,
+    tags: Option<&str>,
+    exclude: Option<&str>,
+    canary: Option<&str>,
+) -> Result<Vec<DiscourseConfig>> {
+    let filter = tags.map(parse_tags).unwrap_or_default();
+    let excluded = exclude.map(parse_tags).unwrap_or_default();
+    for name in &excluded {
+        if find_discourse(config, name).is_none() {
+            return Err(anyhow!("discourse not found: {}", name));
+        }
+    }
+    let mut targets: Vec<DiscourseConfig> = config
+        .discourse
+        .iter()
+        .filter(|d| matches_tags(d, &filter) && !excluded.contains(&d.name))
+        .map(|d| with_update_settings(config, d))
+        .collect::<Result<_>>()?;
+    if let Some(canary) = canary {
+        let index = targets
+            .iter()
+            .position(|d| d.name == canary)
+            .ok_or_else(|| anyhow!("canary {} is not among the selected installs", canary))?;
+        let canary = targets.remove(index);
+        targets.insert(0, canary);
+    }
+    if targets.is_empty() && !(filter.is_empty() && excluded.is_empty()) {
+        return Err(anyhow!("no discourses matched the given tags/exclusions"));
+    }
+    Ok(targets)
+}
+
+/// Update the given installs, carrying on past failures, then print a summary table.
+///
+/// With `waves`, the first install is a canary: it is updated and health
+/// checked alone, and the rest follow in batches until the canary or the
+/// failure rate fails. `report` additionally writes the table as JSON
+/// (`.json`) or Markdown (any other extension).
+pub fn update_all(
+    targets: &[DiscourseConfig],
+    parallel: bool,
+    max: Option<usize>,
+    options: UpdateOptions,
+    waves: Option<WaveOptions>,
+    report: Option<&Path>,
+) -> Result<UpdateRunStatus> {
+    let tracker = start_run(targets, options, waves)?;
+    run_fleet(targets, parallel, max, options, waves, report, &tracker)
+}
+
+fn run_fleet(
+    targets: &[DiscourseConfig],
+    parallel: bool,
+    max: Option<usize>,
+    options: UpdateOptions,
+    waves: Option<WaveOptions>,
+    report: Option<&Path>,
+    tracker: &RunTracker,
+) -> Result<UpdateRunStatus> {
+    let entries = match waves {
+        None => run_updates(targets, parallel, max, options, tracker),
+        Some(waves) => run_update_waves(targets, parallel, max, options, waves, tracker),
+    };
+    tracker.finish();
+
+    println!("\nUpdate report:");
+    print!("{}", render_update_report_text(&entries));
+    if !entries.is_empty() {
+        println!("Logs: {}", tracker.log_root().display());
+    }
+    if entries.iter().any(|entry| entry.error.is_some()) {
+        print_resume_hint(tracker);
+    }
+    if let Some(path) = report {
+        let raw = if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+            serde_json::to_string_pretty(&entries)?
+        } else {
+            render_update_report_markdown(&entries)
+        };
+        std::fs::write(path, raw).with_context(|| format!("writing {}", path.display()))?;
+        println!("Report written to {}", path.display());
+    }
+    Ok(UpdateRunStatus::from_entries(&entries))
+}
+
+fn run_update_waves(
+    targets: &[DiscourseConfig],
+    parallel: bool,
+    max: Option<usize>,
+    options: UpdateOptions,
+    waves: WaveOptions,
+    tracker: &RunTracker,
+) -> Vec<UpdateReportEntry> {
+    let options = UpdateOptions {
+        health_check: true,
+        ..options
+    };
+    let Some((canary, rest)) = targets.split_first() else {
+        return Vec::new();
+    };
+    println!("\n==> Wave 0: canary {}", canary.name);
+    let mut entries = vec![update_and_report(canary, options, tracker)];
+    let mut stop_reason = entries[0]
+        .error
+        .is_some()
+        .then(|| format!("canary {} failed", canary.name));
+
+    let mut remaining = rest;
+    let mut wave = 1;
+    while stop_reason.is_none() && !remaining.is_empty() {
+        let (batch, rest) = remaining.split_at(waves.batch_size.max(1).min(remaining.len()));
+        let names: Vec<&str> = batch.iter().map(|d| d.name.as_str()).collect();
+        println!("\n==> Wave {}: {}", wave, names.join(", "));
+        entries.extend(run_updates(batch, parallel, max, options, tracker));
+        let rate = failure_rate(&entries);
+        if rate > waves.max_failure_rate {
+            stop_reason = Some(format!(
+                "failure rate {:.0}% exceeds {:.0}%",
+                rate, waves.max_failure_rate
+            ));
+        }
+        remaining = rest;
+        wave += 1;
+    }
+
+    if let Some(reason) = stop_reason {
+        if !remaining.is_empty() {
+            eprintln!(
+                "Stopping rollout: {}; {} install(s) skipped",
+                reason,
+                remaining.len()
+            );
+        }
+        for discourse in remaining {
+            let mut entry = UpdateReportEntry::new(&discourse.name);
+            entry.status = "skipped";
+            entry.error = Some(format!("skipped: {}", reason));
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+/// Percentage of report entries that failed.
+fn failure_rate(entries: &[UpdateReportEntry]) -> f64 {
+    if entries.is_empty() {
+        return 0.0;
+    }
+    let failed = entries.iter().filter(|entry| entry.error.is_some()).count();
+    failed as f64 * 100.0 / entries.len() as f64
+}
+
+fn run_updates(
+    targets: &[DiscourseConfig],
+    parallel: bool,
+    max: Option<usize>,
+    options: UpdateOptions,
+    tracker: &RunTracker,
+) -> Vec<UpdateReportEntry> {
+    if !parallel {
+        return targets
+            .iter()
+            .map(|discourse| update_and_report(discourse, options, tracker))
+            .collect();
+    }
+
+    let mut entries = Vec::new();
+    let max_threads = parallel_worker_count(max, targets.len());
+    let mut handles: VecDeque<(String, thread::JoinHandle<UpdateReportEntry>)> = VecDeque::new();
+    for discourse in targets.iter().cloned() {
+        if handles.len() >= max_threads
+            && let Some((name, handle)) = handles.pop_front()
+        {
+            entries.push(join_update(&name, handle));
+        }
+        let name = discourse.name.clone();
+        let tracker = tracker.clone();
+        handles.push_back((
+            name,
+            thread::spawn(move || update_and_report(&discourse, options, &tracker)),
+        ));
+    }
+    for (name, handle) in handles {
+        entries.push(join_update(&name, handle));
+    }
+    // Report in target order rather than completion order.
+    entries.sort_by_key(|entry| {
+        targets
+            .iter()
+            .position(|d| d.name == entry.name)
+            .unwrap_or(usize::MAX)
+    });
+    entries
+}
+
+/// Post-update health check: the version must be readable and the homepage must load.
+fn health_check(discourse: &DiscourseConfig) -> Result<()> {
+    let client = DiscourseClient::new(discourse)?;
+    client
+        .fetch_version_info()
+        .context("fetching version info")?;
+    client.check_page("/")
+}
+
+/// One row of the `dsc update all` report.
+#[derive(Debug, Serialize)]
+struct UpdateReportEntry {
+    name: String,
+    before_version: Option<String>,
+    after_version: Option<String>,
+    duration_secs: u64,
+    reclaimed_space: Option<String>,
+    backup_filename: Option<String>,
+    status: &'static str,
+    error: Option<String>,
+}
+
+impl UpdateReportEntry {
+    fn new(name: &str) -> Self {
+        UpdateReportEntry {
+            name: name.to_string(),
+            before_version: None,
+            after_version: None,
+            duration_secs: 0,
+            reclaimed_space: None,
+            backup_filename: None,
+            status: "ok",
+            error: None,
         }
-        let do_post = post_changelog;
-        let auto_yes = yes;
-        handles.push(thread::spawn(move || {
-            let metadata = run_update(&discourse)?;
-            let payload = print_update_summary(&discourse, &metadata);
-            if do_post {
-                handle_changelog_post(&discourse, &payload, auto_yes)?;
+    }
+}
+
+fn update_and_report(
+    discourse: &DiscourseConfig,
+    options: UpdateOptions,
+    tracker: &RunTracker,
+) -> UpdateReportEntry {
+    let started = Instant::now();
+    let mut entry = UpdateReportEntry::new(&discourse.name);
+    tracker.record(&discourse.name, |install| {
+        install.status = InstallStatus::Running
+    });
+    let log_dir = tracker.log_dir(&discourse.name);
+    notify_update(discourse, tracker, NotifyStage::Started, None, None);
+    match run_update(discourse, options, tracker) {
+        Ok(metadata) => {
+            let changelog = print_update_summary(discourse, &metadata, &log_dir);
+            if options.health_check {
+                stage(&discourse.name, "Running health check");
+                if let Err(err) = health_check(discourse) {
+                    eprintln!("Health check failed for {}: {:#}", discourse.name, err);
+                    entry.error = Some(format!("health check failed: {:#}", err));
+                }
+            }
+            if entry.error.is_none()
+                && options.post_changelog
+                && let Err(err) = post_changelog_once(discourse, &changelog, options.yes, tracker)
+            {
+                entry.error = Some(format!("changelog post failed: {:#}", err));
             }
-            Ok::<_, anyhow::Error>(())
-        }));
+            if let Some(failure) = metadata.smoke_failure {
+                // Keep a health-check or changelog failure alongside it.
+                let smoke = format!("smoke checks failed: {}", failure);
+                entry.error = Some(match entry.error.take() {
+                    Some(error) => format!("{}; {}", error, smoke),
+                    None => smoke,
+                });
+            }
+            entry.before_version = metadata.before_version;
+            entry.after_version = metadata.after_version;
+            entry.reclaimed_space = metadata.reclaimed_space;
+            entry.backup_filename = metadata.backup_filename;
+        }
+        Err(err) => {
+            eprintln!("Update failed for {}: {:#}", discourse.name, err);
+            eprintln!("Logs: {}", log_dir.display());
+            entry.error = Some(format!("{:#}", err));
+        }
+    }
+    if entry.error.is_some() {
+        entry.status = "failed";
     }
+    record_outcome(tracker, &discourse.name, entry.error.clone());
+    let stage = if entry.error.is_some() {
+        NotifyStage::Failed
+    } else {
+        NotifyStage::Finished
+    };
+    notify_update(
+        discourse,
+        tracker,
+        stage,
+        entry.after_version.clone(),
+        entry.error.clone(),
+    );
+    entry.duration_secs = started.elapsed().as_secs();
+    entry
+}
 
-    for handle in handles {
-        handle.join().expect("thread panicked")?;
+/// Tell the install's webhooks how its update is going; failures also carry
+/// the last stage it completed.
+fn notify_update(
+    discourse: &DiscourseConfig,
+    tracker: &RunTracker,
+    stage: NotifyStage,
+    after_version: Option<String>,
+    error: Option<String>,
+) {
+    let Some(hooks) = discourse
+        .notify
+        .as_deref()
+        .filter(|hooks| !hooks.is_empty())
+    else {
+        return;
+    };
+    let progress = tracker.progress(&discourse.name);
+    let mut event = NotifyEvent::new(NotifyKind::Update, stage, &discourse.name);
+    event.run_id = Some(tracker.id());
+    event.before_version = progress.before_version;
+    event.after_version = after_version;
+    if error.is_some() {
+        event.last_stage = progress.stages.last().map(UpdateStage::label);
     }
+    event.error = error;
+    notify(hooks, &event);
+}
 
-    Ok(())
+fn join_update(name: &str, handle: thread::JoinHandle<UpdateReportEntry>) -> UpdateReportEntry {
+    handle.join().unwrap_or_else(|_| {
+        let mut entry = UpdateReportEntry::new(name);
+        entry.error = Some("update thread panicked".to_string());
+        entry.status = "failed";
+        entry
+    })
+}
+
+fn update_report_row(entry: &UpdateReportEntry) -> [String; 5] {
+    [
+        entry.name.clone(),
+        format!(
+            "{} -> {}",
+            entry.before_version.as_deref().unwrap_or("unknown"),
+            entry.after_version.as_deref().unwrap_or("unknown")
+        ),
+        format_duration(entry.duration_secs),
+        entry
+            .reclaimed_space
+            .clone()
+            .unwrap_or_else(|| "-".to_string()),
+        entry.status.to_string(),
+    ]
+}
+
+const UPDATE_REPORT_HEADERS: [&str; 5] = ["Name", "Version", "Duration", "Reclaimed", "Status"];
+
+fn render_update_report_text(entries: &[UpdateReportEntry]) -> String {
+    let rows: Vec<[String; 5]> = entries.iter().map(update_report_row).collect();
+    let mut out = align_columns(UPDATE_REPORT_HEADERS, &rows);
+    for entry in entries {
+        if let Some(error) = entry.error.as_deref() {
+            out.push_str(&format!("{}: {}\n", entry.name, error));
+        }
+    }
+    out
+}
+
+/// Render a plain-text table with space-aligned columns.
+pub(crate) fn align_columns<const N: usize>(headers: [&str; N], rows: &[[String; N]]) -> String {
+    let mut widths = headers.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let format_row = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    let mut out = String::new();
+    out.push_str(&format_row(&headers.map(String::from)));
+    out.push('\n');
+    for row in rows {
+        out.push_str(&format_row(row));
+        out.push('\n');
+    }
+    out
+}
+
+fn render_update_report_markdown(entries: &[UpdateReportEntry]) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("| {} |\n", UPDATE_REPORT_HEADERS.join(" | ")));
+    out.push_str("| --- | --- | --- | --- | --- |\n");
+    for entry in entries {
+        let mut row = update_report_row(entry);
+        if let Some(error) = entry.error.as_deref() {
+            row[4] = format!("{}: {}", entry.status, error.replace('|', "\\|"));
+        }
+        out.push_str(&format!("| {} |\n", row.join(" | ")));
+    }
+    out
+}
+
+fn format_duration(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
 }
 
-fn parallel_worker_count(max: Option<usize>, discourse_count: usize) -> usize {
+pub(crate) fn parallel_worker_count(max: Option<usize>, discourse_count: usize) -> usize {
     let requested = max.unwrap_or(DEFAULT_PARALLEL_UPDATE_WORKERS).max(1);
     requested.min(discourse_count.max(1))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parallel_worker_count;
+    use super::{
+        ContainerRole, UpdateMetadata, UpdateReportEntry, UpdateRunStatus, build_changelog_payload,
+        changelog_vars, container_rebuild_command, failure_rate, format_duration,
+        parallel_worker_count, parse_container_roles, render_update_report_markdown,
+        scan_rebuild_log, select_update_targets, update_step_stage, with_update_settings,
+        zero_downtime_containers,
+    };
+    use crate::commands::update_state::UpdateStage;
+    use crate::commands::update_template::render_template;
+    use crate::config::{Config, DiscourseConfig};
+
+    fn fleet() -> Config {
+        let discourse = |name: &str, tags: &[&str]| DiscourseConfig {
+            name: name.to_string(),
+            tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+            ..DiscourseConfig::default()
+        };
+        Config {
+            discourse: vec![
+                discourse("alpha", &["prod"]),
+                discourse("legacy-forum", &["prod"]),
+                discourse("beta", &["prod"]),
+                discourse("staging", &["dev"]),
+            ],
+            ..Config::default()
+        }
+    }
+
+    fn report_entry(name: &str, error: Option<&str>) -> UpdateReportEntry {
+        UpdateReportEntry {
+            name: name.to_string(),
+            before_version: Some("3.3.0".to_string()),
+            after_version: Some("3.3.1".to_string()),
+            duration_secs: 75,
+            reclaimed_space: None,
+            backup_filename: None,
+            status: if error.is_some() { "failed" } else { "ok" },
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn update_targets_apply_tags_exclusions_and_canary() {
+        let config = fleet();
+        let targets =
+            select_update_targets(&config, Some("prod"), Some("legacy-forum"), Some("beta"))
+                .expect("targets");
+        let names: Vec<&str> = targets.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["beta", "alpha"]);
+        assert!(select_update_targets(&config, Some("prod"), None, Some("staging")).is_err());
+        assert!(select_update_targets(&config, None, Some("missing"), None).is_err());
+    }
+
+    #[test]
+    fn update_recipe_resolves_named_and_inline_recipes() {
+        let config: Config = toml::from_str(
+            r#"
+            [[discourse]]
+            name = "named"
+            baseurl = "https://named.example"
+            update_recipe = "no-reboot"
+
+            [[discourse]]
+            name = "inline"
+            baseurl = "https://inline.example"
+            update_recipe = "no-reboot"
+            [discourse.update]
+            min_free_gb = 20
+
+            [update_recipes.no-reboot]
+            boot_wait_secs = 60
+            [[update_recipes.no-reboot.steps]]
+            step = "rebuild"
+            command = "cd /var/discourse && ./launcher rebuild data && ./launcher rebuild web_only"
+            timeout_secs = 3600
+            [[update_recipes.no-reboot.steps]]
+            step = "command"
+            name = "warm cache"
+            command = "curl -fsS localhost"
+            "#,
+        )
+        .expect("config");
+
+        let named = with_update_settings(&config, &config.discourse[0]).expect("named");
+        let recipe = named.update.expect("recipe");
+        assert_eq!(recipe.boot_wait_secs, Some(60));
+        let stages: Vec<UpdateStage> = recipe
+            .steps
+            .expect("steps")
+            .iter()
+            .map(update_step_stage)
+            .collect();
+        assert_eq!(
+            stages,
+            [
+                UpdateStage::Rebuilt,
+                UpdateStage::Command("warm cache".to_string())
+            ]
+        );
+
+        let inline = with_update_settings(&config, &config.discourse[1]).expect("inline");
+        let recipe = inline.update.expect("recipe");
+        assert_eq!(recipe.min_free_gb, Some(20));
+        assert!(recipe.steps.is_none());
+    }
+
+    #[test]
+    fn update_recipe_rejects_unknown_names_and_unnamed_commands() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [[discourse]]
+            name = "forum"
+            baseurl = "https://forum.example"
+            update_recipe = "missing"
+
+            [update_recipes.bad]
+            [[update_recipes.bad.steps]]
+            step = "command"
+            command = "true"
+            "#,
+        )
+        .expect("config");
+        assert!(with_update_settings(&config, &config.discourse[0]).is_err());
+        config.discourse[0].update_recipe = Some("bad".to_string());
+        assert!(with_update_settings(&config, &config.discourse[0]).is_err());
+    }
+
+    #[test]
+    fn failure_rate_counts_failed_entries() {
+        assert_eq!(failure_rate(&[]), 0.0);
+        let entries = [
+            report_entry("a", None),
+            report_entry("b", Some("boom")),
+            report_entry("c", None),
+            report_entry("d", None),
+        ];
+        assert_eq!(failure_rate(&entries), 25.0);
+    }
+
+    #[test]
+    fn containers_rebuild_data_first_and_web_last() {
+        let output = "web_only 10\nmail-receiver 00\ndata 01\nbroken\napp 11\n";
+        let roles = parse_container_roles(output);
+        let names: Vec<&str> = roles.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["data", "mail-receiver", "app", "web_only"]);
+        assert_eq!(roles[3].1, ContainerRole::Web);
+    }
+
+    #[test]
+    fn zero_downtime_bootstraps_only_web_containers() {
+        let web = container_rebuild_command("web_only", ContainerRole::Web, true);
+        assert!(web.contains("./launcher bootstrap web_only && "));
+        assert!(web.ends_with("./launcher start web_only"));
+        let app = container_rebuild_command("app", ContainerRole::Standalone, true);
+        assert!(app.ends_with("./launcher rebuild app"));
+        let web = container_rebuild_command("web_only", ContainerRole::Web, false);
+        assert!(web.ends_with("./launcher rebuild web_only"));
+    }
+
+    #[test]
+    fn zero_downtime_leaves_data_containers_running() {
+        let containers = parse_container_roles(
+            "web_only 10
+data 01
+mail-receiver 00
+",
+        );
+        let (web, skipped) = zero_downtime_containers(containers).expect("web container");
+        assert_eq!(web, [("web_only".to_string(), ContainerRole::Web)]);
+        assert_eq!(skipped, ["data", "mail-receiver"]);
+    }
+
+    #[test]
+    fn zero_downtime_requires_a_web_container() {
+        let err = zero_downtime_containers(parse_container_roles(
+            "app 11
+",
+        ))
+        .unwrap_err();
+        assert!(err.to_string().contains("(found: app)"), "{}", err);
+        assert!(zero_downtime_containers(Vec::new()).is_err());
+    }
 
     #[test]
     fn default_parallel_workers_is_three() {
@@ -88,8 +992,138 @@ mod tests {
     fn max_workers_is_capped_by_discourse_count() {
         assert_eq!(parallel_worker_count(Some(8), 2), 2);
     }
+
+    #[test]
+    fn update_run_status_distinguishes_partial_failure() {
+        let ok = report_entry("a", None);
+        let failed = report_entry("b", Some("boom"));
+        assert_eq!(
+            UpdateRunStatus::from_entries(&[report_entry("a", None)]),
+            UpdateRunStatus::AllOk
+        );
+        assert_eq!(
+            UpdateRunStatus::from_entries(&[ok, report_entry("b", Some("boom"))]),
+            UpdateRunStatus::SomeFailed
+        );
+        assert_eq!(
+            UpdateRunStatus::from_entries(&[failed]),
+            UpdateRunStatus::AllFailed
+        );
+    }
+
+    #[test]
+    fn markdown_report_includes_errors() {
+        let markdown = render_update_report_markdown(&[
+            report_entry("a", None),
+            report_entry("b", Some("rebuild | failed")),
+        ]);
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines[2], "| a | 3.3.0 -> 3.3.1 | 1m 15s | - | ok |");
+        assert_eq!(
+            lines[3],
+            "| b | 3.3.0 -> 3.3.1 | 1m 15s | - | failed: rebuild \\| failed |"
+        );
+        assert_eq!(format_duration(3725), "1h 02m");
+    }
+
+    #[test]
+    fn changelog_payload_lists_backup_and_smoke_failure() {
+        let metadata = UpdateMetadata {
+            before_version: None,
+            before_commit: None,
+            after_version: None,
+            after_commit: None,
+            reclaimed_space: None,
+            before_os_version: None,
+            after_version_error: None,
+            root_disk_usage: None,
+            os_updated: true,
+            server_rebooted: false,
+            backup_filename: Some("forum-2026-10-19-020000-v1.tar.gz".to_string()),
+            smoke_checked: true,
+            smoke_failure: Some("/srv/status request failed with 502 Bad Gateway".to_string()),
+            rollback: Some("Rolled back to the previous image (app)".to_string()),
+            duration_secs: 75,
+        };
+        let payload = build_changelog_payload(&metadata);
+        let lines: Vec<&str> = payload.lines().collect();
+        assert_eq!(
+            lines[0],
+            "- [x] Backup created: forum-2026-10-19-020000-v1.tar.gz"
+        );
+        assert_eq!(
+            lines[lines.len() - 2..],
+            [
+                "- [ ] Smoke checks failed: /srv/status request failed with 502 Bad Gateway",
+                "  - Rolled back to the previous image (app)",
+            ]
+        );
+    }
+
+    #[test]
+    fn changelog_settings_merge_and_render_metadata() {
+        let config: Config = toml::from_str(
+            r#"
+            [changelog]
+            template = "changelog.md"
+            title = "global title"
+
+            [[discourse]]
+            name = "forum"
+            baseurl = "https://forum.example/"
+            [discourse.changelog]
+            category_id = 7
+            title = "{{ name }}: {{ after_version | default \"unknown\" }}"
+            "#,
+        )
+        .expect("config");
+        let discourse = with_update_settings(&config, &config.discourse[0]).expect("settings");
+        let changelog = discourse.changelog.clone().expect("changelog");
+        assert_eq!(
+            changelog.template.as_deref(),
+            Some(std::path::Path::new("changelog.md"))
+        );
+        assert_eq!(changelog.category_id, Some(7));
+        let title = changelog.title.expect("title");
+
+        let metadata = UpdateMetadata {
+            before_commit: Some("aaa".to_string()),
+            after_commit: Some("bbb".to_string()),
+            server_rebooted: true,
+            duration_secs: 75,
+            ..UpdateMetadata::default()
+        };
+        let vars = changelog_vars(&discourse, &metadata, "default body", None);
+        assert_eq!(
+            render_template(&title, &vars).expect("title"),
+            "forum: unknown"
+        );
+        let body = "{{ baseurl }} took {{ duration }}{% if rebooted %}, rebooted{% endif %}\n\
+            {{ release_notes_url }}\n{{ default }}";
+        assert_eq!(
+            render_template(body, &vars).expect("body"),
+            "https://forum.example took 1m 15s, rebooted\n\
+            https://github.com/discourse/discourse/compare/aaa...bbb\ndefault body"
+        );
+    }
+
+    #[test]
+    fn rebuild_log_scan_reports_plugin_errors() {
+        let log = "I, Bundle complete!\n\x1b[31mrake aborted!\x1b[0m\n\
+            LoadError: cannot load such file -- /var/www/discourse/plugins/foo/lib/bar\n";
+        let patterns = vec!["rake aborted!".to_string(), "LoadError".to_string()];
+        assert_eq!(
+            scan_rebuild_log(log, &patterns),
+            [
+                "rake aborted!",
+                "LoadError: cannot load such file -- /var/www/discourse/plugins/foo/lib/bar",
+            ]
+        );
+        assert!(scan_rebuild_log(log, &[]).is_empty());
+    }
 }
 
+#[derive(Default)]
 struct UpdateMetadata {
     before_version: Option<String>,
     before_commit: Option<String>,
@@ -101,9 +1135,21 @@ struct UpdateMetadata {
     root_disk_usage: Option<String>,
     os_updated: bool,
     server_rebooted: bool,
+    backup_filename: Option<String>,
+    smoke_checked: bool,
+    smoke_failure: Option<String>,
+    rollback: Option<String>,
+    duration_secs: u64,
 }
 
-fn run_update(discourse: &DiscourseConfig) -> Result<UpdateMetadata> {
+/// Run the update workflow, skipping stages `tracker` already records as done
+/// for this install (when resuming) and recording each stage as it completes.
+fn run_update(
+    discourse: &DiscourseConfig,
+    options: UpdateOptions,
+    tracker: &RunTracker,
+) -> Result<UpdateMetadata> {
+    let started = Instant::now();
     let client = DiscourseClient::new(discourse)?;
     let target = discourse
         .ssh_host
@@ -111,148 +1157,361 @@ fn run_update(discourse: &DiscourseConfig) -> Result<UpdateMetadata> {
         .unwrap_or_else(|| discourse.name.clone());
     let discourse_label = colored_discourse_display(discourse);
     println!("\n==> Updating {} ({})", discourse_label, target);
-    stage(
-        &discourse_label,
-        "Fetching Discourse version (before update)",
-    );
-    let before_info = match client.fetch_version_info() {
-        Ok(info) => {
-            let label = info.version.as_deref().unwrap_or("unknown");
-            stage(
-                &discourse_label,
-                &format!("Initial Discourse Version (before update): {}", label),
-            );
-            info
+    let recipe = discourse.update.clone().unwrap_or_default();
+    let progress = tracker.progress(&discourse.name);
+    if let Some(last) = progress.stages.last() {
+        stage(
+            &discourse_label,
+            &format!("Resuming after stage: {}", last.label()),
+        );
+    }
+    let resumed = progress.has(&UpdateStage::DiskChecked);
+    let before_info = if resumed {
+        stage(
+            &discourse_label,
+            &format!(
+                "Initial Discourse Version (before update): {} (recorded)",
+                progress.before_version.as_deref().unwrap_or("unknown")
+            ),
+        );
+        VersionInfo {
+            version: progress.before_version.clone(),
+            commit: progress.before_commit.clone(),
         }
-        Err(err) => {
-            stage(
-                &discourse_label,
-                &format!(
-                    "Initial Discourse Version (before update): unknown (fetch failed: {})",
-                    err
-                ),
-            );
-            VersionInfo {
-                version: None,
-                commit: None,
+    } else {
+        stage(
+            &discourse_label,
+            "Fetching Discourse version (before update)",
+        );
+        match client.fetch_version_info() {
+            Ok(info) => {
+                let label = info.version.as_deref().unwrap_or("unknown");
+                stage(
+                    &discourse_label,
+                    &format!("Initial Discourse Version (before update): {}", label),
+                );
+                info
+            }
+            Err(err) => {
+                stage(
+                    &discourse_label,
+                    &format!(
+                        "Initial Discourse Version (before update): unknown (fetch failed: {})",
+                        err
+                    ),
+                );
+                VersionInfo {
+                    version: None,
+                    commit: None,
+                }
             }
         }
     };
-    stage(&discourse_label, "Fetching OS details");
-    let before_os_version = match get_os_version(&target) {
-        Ok(version) => {
-            let label = version.as_deref().unwrap_or("unknown");
-            stage(&discourse_label, &format!("OS: {}", label));
-            version
-        }
-        Err(err) => {
-            stage(
-                &discourse_label,
-                &format!(
-                    "Initial OS Version (before update): unknown (fetch failed: {})",
-                    err
-                ),
-            );
-            None
+    let before_os_version = if resumed {
+        progress.before_os_version.clone()
+    } else {
+        stage(&discourse_label, "Fetching OS details");
+        match get_os_version(&target) {
+            Ok(version) => {
+                let label = version.as_deref().unwrap_or("unknown");
+                stage(&discourse_label, &format!("OS: {}", label));
+                version
+            }
+            Err(err) => {
+                stage(
+                    &discourse_label,
+                    &format!(
+                        "Initial OS Version (before update): unknown (fetch failed: {})",
+                        err
+                    ),
+                );
+                None
+            }
         }
     };
 
-    stage(&discourse_label, "Checking root disk free space");
-    let min_free_gb = std::env::var("DSC_DISCOURSE_MIN_FREE_GB")
-        .ok()
-        .and_then(|raw| raw.trim().parse::<u64>().ok())
-        .filter(|gb| *gb > 0)
-        .unwrap_or(5);
-    if let Some(available_gb) = get_root_disk_available_gb(&target)? {
-        if available_gb < min_free_gb {
-            return Err(anyhow!(
-                "insufficient disk space on {}: {}G free (minimum {}G). Please run an interactive update via SSH to clean up space, then retry.",
-                target,
-                available_gb,
-                min_free_gb
-            ));
+    if !resumed {
+        stage(&discourse_label, "Checking root disk free space");
+        let min_free_gb = min_free_gb(&recipe);
+        if let Some(available_gb) = get_root_disk_available_gb(&target)? {
+            if available_gb < min_free_gb {
+                return Err(anyhow!(
+                    "insufficient disk space on {}: {}G free (minimum {}G). Please run an interactive update via SSH to clean up space, then retry.",
+                    target,
+                    available_gb,
+                    min_free_gb
+                ));
+            }
         }
+        tracker.record(&discourse.name, |install| {
+            install.before_version = before_info.version.clone();
+            install.before_commit = before_info.commit.clone();
+            install.before_os_version = before_os_version.clone();
+            install.complete(UpdateStage::DiskChecked);
+        });
     }
 
-    let os_update_cmd = std::env::var("DSC_SSH_OS_UPDATE_CMD").unwrap_or_else(|_| {
-        "sudo -n DEBIAN_FRONTEND=noninteractive apt update && sudo -n DEBIAN_FRONTEND=noninteractive apt upgrade -y"
-            .to_string()
-    });
-    let reboot_cmd =
-        std::env::var("DSC_SSH_REBOOT_CMD").unwrap_or_else(|_| "sudo -n reboot".to_string());
-    let discourse_update_cmd = std::env::var("DSC_SSH_UPDATE_CMD")
-        .unwrap_or_else(|_| "cd /var/discourse && sudo -n ./launcher rebuild app".to_string());
-    let cleanup_cmd = std::env::var("DSC_SSH_CLEANUP_CMD")
-        .unwrap_or_else(|_| {
-            // ./launcher cleanup runs docker container prune + docker image prune, both of which
-            // prompt for [y/N] confirmation. Without a TTY, they read EOF and default to N,
-            // silently doing nothing. Use -f to skip confirmation in non-interactive SSH.
-            "sudo -n docker container prune -f && sudo -n docker image prune -f".to_string()
+    let mut backup_filename = progress.backup_filename.clone();
+    if progress.has(&UpdateStage::BackedUp) {
+        stage(
+            &discourse_label,
+            &format!(
+                "Backup already created: {}",
+                backup_filename.as_deref().unwrap_or("unknown")
+            ),
+        );
+    } else if options.backup_first || discourse.backup_before_update == Some(true) {
+        stage(&discourse_label, "Creating backup (before update)");
+        ensure_api_credentials(discourse)?;
+        let filename = create_backup_and_wait(&client, backup_timeout(None))
+            .map_err(|err| anyhow!("backup before update failed for {}: {:#}", target, err))?;
+        stage(&discourse_label, &format!("Backup created: {}", filename));
+        tracker.record(&discourse.name, |install| {
+            install.backup_filename = Some(filename.clone());
+            install.complete(UpdateStage::BackedUp);
         });
+        backup_filename = Some(filename);
+    }
 
+    let mut os_updated = false;
     let mut server_rebooted = false;
-
-    stage(&discourse_label, "Running OS update");
-    if let Err(err) = run_ssh_command_with_tail(&target, &os_update_cmd, "OS update in progress", 3)
+    if progress.stages.last() == Some(&UpdateStage::Rebooted)
+        && !ssh_probe(&target).unwrap_or(false)
     {
-        if let Some(rollback_cmd) = os_update_rollback_cmd() {
-            stage(&discourse_label, "Running OS update rollback");
-            if let Err(rollback_err) = run_ssh_command(&target, &rollback_cmd) {
-                eprintln!(
-                    "Warning: OS update rollback failed for {}: {}",
-                    target, rollback_err
-                );
-            }
-        }
-        return Err(anyhow!("OS update failed for {}: {}", target, err));
+        stage(&discourse_label, "Waiting for server to come back online");
+        wait_for_ssh(&target, &discourse_label, None)?;
     }
-    let os_updated = true;
-    stage(&discourse_label, "Rebooting server");
-    if run_ssh_command(&target, &reboot_cmd).is_ok() {
-        server_rebooted = true;
-        if std::env::var("DSC_SSH_OS_UPDATE_CMD").unwrap_or_default() != "echo OS packages updated"
+    let mut reclaimed_space = progress.reclaimed_space.clone();
+    let mut rebuild_log = String::new();
+    let mut smoke_checked = false;
+    let mut smoke_failure = None;
+    let mut rollback = None;
+    let log_dir = tracker.log_dir(&discourse.name);
+    for step in recipe.steps.clone().unwrap_or_else(default_update_steps) {
+        let step_stage = update_step_stage(&step);
+        let label = update_step_label(&step);
+        if progress.has(&step_stage) {
+            stage(
+                &discourse_label,
+                &format!("Step already done in this run: {}", label),
+            );
+            os_updated |= step.step == UpdateStepKind::OsUpdate;
+            server_rebooted |= step.step == UpdateStepKind::Reboot;
+            continue;
+        }
+        if step.skip {
+            stage(&discourse_label, &format!("Skipping step: {}", label));
+            continue;
+        }
+        if let Some(condition) = step.skip_if.as_deref()
+            && run_ssh_command(&target, condition).is_ok()
         {
-            stage(&discourse_label, "Waiting for server to come back online");
-            std::thread::sleep(std::time::Duration::from_secs(30));
-            let mut attempts = 0;
-            let max_attempts = 12;
-            while attempts < max_attempts {
-                match ssh_probe(&target) {
-                    Ok(true) => break,
-                    Ok(false) | Err(_) => {
-                        attempts += 1;
-                        if attempts < max_attempts {
-                            println!(
-                                "[{}] Still waiting for SSH (attempt {}/{})",
-                                discourse_label,
-                                attempts + 1,
-                                max_attempts
+            stage(
+                &discourse_label,
+                &format!("Skipping step: {} (skip_if matched)", label),
+            );
+            continue;
+        }
+        let command = update_step_command(&step)?;
+        let timeout = step.timeout_secs.map(Duration::from_secs);
+        match step.step {
+            UpdateStepKind::Reboot => {
+                stage(&discourse_label, "Rebooting server");
+                let mut log = StageLog::open(&log_dir, &label, &command);
+                if run_ssh_command_logged(&target, &command, &mut log).is_err() {
+                    continue;
+                }
+                server_rebooted = true;
+                tracker.complete(&discourse.name, step_stage);
+                if std::env::var("DSC_SSH_OS_UPDATE_CMD").unwrap_or_default()
+                    != "echo OS packages updated"
+                {
+                    stage(&discourse_label, "Waiting for server to come back online");
+                    std::thread::sleep(std::time::Duration::from_secs(30));
+                    wait_for_ssh(&target, &discourse_label, timeout)?;
+                }
+            }
+            UpdateStepKind::Cleanup => {
+                stage(&discourse_label, "Running cleanup");
+                let cleanup = run_ssh_command_with_tail(
+                    &target,
+                    &command,
+                    "Cleanup in progress",
+                    0,
+                    timeout,
+                    &mut StageLog::open(&log_dir, &label, &command),
+                )?;
+                reclaimed_space = parse_reclaimed_space(&cleanup);
+                tracker.record(&discourse.name, |install| {
+                    install.reclaimed_space = reclaimed_space.clone();
+                    install.complete(step_stage);
+                });
+            }
+            kind => {
+                let (message, progress_message) = match kind {
+                    UpdateStepKind::OsUpdate => (
+                        "Running OS update".to_string(),
+                        "OS update in progress".to_string(),
+                    ),
+                    UpdateStepKind::Rebuild => (
+                        "Running Discourse update".to_string(),
+                        "Discourse update in progress".to_string(),
+                    ),
+                    _ => (
+                        format!("Running step: {}", label),
+                        format!("{} in progress", label),
+                    ),
+                };
+                stage(&discourse_label, &message);
+                let commands = if kind == UpdateStepKind::Rebuild {
+                    rebuild_plan(
+                        &target,
+                        &discourse_label,
+                        &step,
+                        options.zero_downtime || step.zero_downtime,
+                    )?
+                } else {
+                    vec![(None, command)]
+                };
+                // Containers rebuilt before a resume already run the new image,
+                // so a snapshot now would save that as the previous one.
+                let partly_rebuilt = progress
+                    .stages
+                    .iter()
+                    .any(|stage| matches!(stage, UpdateStage::ContainerRebuilt(_)));
+                let snapshots = if kind == UpdateStepKind::Rebuild
+                    && step.rollback.is_none()
+                    && recipe
+                        .smoke
+                        .as_ref()
+                        .is_some_and(|smoke| smoke.rollback != Some(false))
+                {
+                    if partly_rebuilt {
+                        stage(
+                            &discourse_label,
+                            "Not saving the previous image: some containers were already rebuilt in this run",
+                        );
+                        Vec::new()
+                    } else {
+                        snapshot_app_images(&target, &discourse_label)
+                    }
+                } else {
+                    Vec::new()
+                };
+                for (container, command) in commands {
+                    let container_stage = container.clone().map(UpdateStage::ContainerRebuilt);
+                    if let Some(container_stage) = container_stage.as_ref() {
+                        let name = container.as_deref().unwrap_or_default();
+                        if progress.has(container_stage) {
+                            stage(
+                                &discourse_label,
+                                &format!("Container already rebuilt in this run: {}", name),
                             );
-                            std::thread::sleep(std::time::Duration::from_secs(30));
+                            continue;
                         }
+                        stage(&discourse_label, &format!("Rebuilding container: {}", name));
+                    }
+                    let log_name = match container.as_deref() {
+                        Some(name) => format!("{} {}", label, name),
+                        None => label.clone(),
+                    };
+                    let output = run_ssh_command_with_tail(
+                        &target,
+                        &command,
+                        &progress_message,
+                        3,
+                        timeout,
+                        &mut StageLog::open(&log_dir, &log_name, &command),
+                    );
+                    if let Err(err) = output {
+                        if let Some(rollback_cmd) = update_step_rollback(&step) {
+                            stage(&discourse_label, &format!("Running {} rollback", label));
+                            let rollback_cmd = expand_rollback_command(&rollback_cmd, &before_info);
+                            let mut log = StageLog::open(
+                                &log_dir,
+                                &format!("{} rollback", label),
+                                &rollback_cmd,
+                            );
+                            if let Err(rollback_err) =
+                                run_ssh_command_logged(&target, &rollback_cmd, &mut log)
+                            {
+                                eprintln!(
+                                    "Warning: {} rollback failed for {}: {}",
+                                    label, target, rollback_err
+                                );
+                            }
+                        }
+                        return Err(match kind {
+                            UpdateStepKind::OsUpdate => {
+                                anyhow!("OS update failed for {}: {}", target, err)
+                            }
+                            UpdateStepKind::Rebuild => match container {
+                                Some(name) => {
+                                    anyhow!("rebuilding container {} failed: {}", name, err)
+                                }
+                                None => err,
+                            },
+                            _ => anyhow!("{} failed for {}: {}", label, target, err),
+                        });
+                    }
+                    if kind == UpdateStepKind::Rebuild
+                        && let Ok(output) = output
+                    {
+                        rebuild_log.push_str(&output);
+                    }
+                    if let Some(container_stage) = container_stage {
+                        tracker.complete(&discourse.name, container_stage);
                     }
                 }
-            }
-            if attempts >= max_attempts {
-                return Err(anyhow!("Server did not come back online after reboot"));
+                os_updated |= kind == UpdateStepKind::OsUpdate;
+                if kind == UpdateStepKind::Rebuild {
+                    stage(&discourse_label, "Waiting for Discourse to serve pages");
+                    let wait_secs = recipe
+                        .boot_wait_secs
+                        .or_else(|| {
+                            std::env::var("DSC_DISCOURSE_BOOT_WAIT_SECS")
+                                .ok()
+                                .and_then(|raw| raw.trim().parse::<u64>().ok())
+                        })
+                        .filter(|secs| *secs > 0)
+                        .unwrap_or(15);
+                    std::thread::sleep(std::time::Duration::from_secs(wait_secs));
+                    if let Some(smoke) = recipe.smoke.as_ref() {
+                        smoke_checked = true;
+                        stage(&discourse_label, "Running smoke checks");
+                        if let Err(err) = run_smoke_checks(discourse, &client, smoke, &rebuild_log)
+                        {
+                            let failure = format!("{:#}", err);
+                            stage(
+                                &discourse_label,
+                                &format!("Smoke checks failed: {}", failure),
+                            );
+                            if smoke.rollback != Some(false) {
+                                rollback = Some(roll_back_rebuild(
+                                    &target,
+                                    &discourse_label,
+                                    &step,
+                                    &snapshots,
+                                    &before_info,
+                                    &log_dir,
+                                ));
+                            }
+                            smoke_failure = Some(failure);
+                            // Rebuild every container again when the run is resumed.
+                            tracker.record(&discourse.name, |install| {
+                                install.stages.retain(|stage| {
+                                    !matches!(stage, UpdateStage::ContainerRebuilt(_))
+                                })
+                            });
+                            break;
+                        }
+                        stage(&discourse_label, "Smoke checks passed");
+                    }
+                }
+                tracker.complete(&discourse.name, step_stage);
             }
         }
     }
-
-    stage(&discourse_label, "Running Discourse update");
-    run_ssh_command_with_tail(
-        &target,
-        &discourse_update_cmd,
-        "Discourse update in progress",
-        3,
-    )?;
-    stage(&discourse_label, "Waiting for Discourse to serve pages");
-    let wait_secs = std::env::var("DSC_DISCOURSE_BOOT_WAIT_SECS")
-        .ok()
-        .and_then(|raw| raw.trim().parse::<u64>().ok())
-        .filter(|secs| *secs > 0)
-        .unwrap_or(15);
-    std::thread::sleep(std::time::Duration::from_secs(wait_secs));
     stage(
         &discourse_label,
         "Fetching Discourse version (after update)",
@@ -283,9 +1542,6 @@ fn run_update(discourse: &DiscourseConfig) -> Result<UpdateMetadata> {
             }
         }
     };
-    stage(&discourse_label, "Running cleanup");
-    let cleanup = run_ssh_command_combined(&target, &cleanup_cmd)?;
-    let reclaimed_space = parse_reclaimed_space(&cleanup);
     // No OS version check after update; routine updates don't upgrade OS versions.
     stage(&discourse_label, "Fetching root disk usage");
     let root_disk_usage = match get_root_disk_usage(&target) {
@@ -310,9 +1566,35 @@ fn run_update(discourse: &DiscourseConfig) -> Result<UpdateMetadata> {
         root_disk_usage,
         os_updated,
         server_rebooted,
+        backup_filename,
+        smoke_checked,
+        smoke_failure,
+        rollback,
+        duration_secs: started.elapsed().as_secs(),
     })
 }
 
+/// Poll SSH every 30s until the server answers again after a reboot
+/// (12 attempts unless `timeout` says otherwise).
+fn wait_for_ssh(target: &str, discourse_label: &str, timeout: Option<Duration>) -> Result<()> {
+    let max_attempts = timeout.map_or(12, |timeout| (timeout.as_secs() / 30).max(1));
+    for attempt in 1..=max_attempts {
+        if let Ok(true) = ssh_probe(target) {
+            return Ok(());
+        }
+        if attempt < max_attempts {
+            println!(
+                "[{}] Still waiting for SSH (attempt {}/{})",
+                discourse_label,
+                attempt + 1,
+                max_attempts
+            );
+            std::thread::sleep(std::time::Duration::from_secs(30));
+        }
+    }
+    Err(anyhow!("Server did not come back online after reboot"))
+}
+
 pub(crate) fn run_ssh_command(target: &str, command: &str) -> Result<String> {
     let mut cmd = build_ssh_command(target, &[])?;
     let output = cmd
@@ -329,23 +1611,17 @@ pub(crate) fn run_ssh_command(target: &str, command: &str) -> Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-fn run_ssh_command_combined(target: &str, command: &str) -> Result<String> {
-    let mut cmd = build_ssh_command(target, &[])?;
-    let output = cmd
-        .arg(command)
-        .output()
-        .with_context(|| format!("running ssh to {}: {}", target, command))?;
-    if !output.status.success() {
-        return Err(anyhow!(
-            "ssh command failed for {}: {}",
-            target,
-            String::from_utf8_lossy(&output.stderr)
-        ));
+/// `run_ssh_command`, with the command's output captured to `log`.
+fn run_ssh_command_logged(target: &str, command: &str, log: &mut StageLog) -> Result<String> {
+    let result = run_ssh_command(target, command);
+    match &result {
+        Ok(output) => {
+            log.lines("out", output);
+            log.line("dsc", "exited with exit status: 0");
+        }
+        Err(err) => log.lines("err", &err.to_string()),
     }
-    let mut combined = String::new();
-    combined.push_str(&String::from_utf8_lossy(&output.stdout));
-    combined.push_str(&String::from_utf8_lossy(&output.stderr));
-    Ok(combined)
+    result
 }
 
 struct LineEvent {
@@ -353,11 +1629,15 @@ struct LineEvent {
     line: String,
 }
 
+/// Run a remote command while showing a rolling tail of its output; returns
+/// stdout followed by stderr. The command is killed once `timeout` passes.
 fn run_ssh_command_with_tail(
     target: &str,
     command: &str,
     message: &str,
     tail_lines: usize,
+    timeout: Option<Duration>,
+    log: &mut StageLog,
 ) -> Result<String> {
     let use_progress = io::stderr().is_terminal();
     let pb = if use_progress {
@@ -424,9 +1704,25 @@ fn run_ssh_command_with_tail(
     let base = format!("[{}] {}", target, message);
     pb.set_message(base.clone());
 
+    let started = Instant::now();
     loop {
+        if let Some(timeout) = timeout
+            && started.elapsed() > timeout
+        {
+            let _ = child.kill();
+            let _ = child.wait();
+            pb.finish_and_clear();
+            log.line("dsc", &format!("timed out after {}s", timeout.as_secs()));
+            return Err(anyhow!(
+                "ssh command timed out after {}s for {}: {}",
+                timeout.as_secs(),
+                target,
+                command
+            ));
+        }
         match rx.recv_timeout(Duration::from_millis(200)) {
             Ok(event) => {
+                log.line(if event.is_stderr { "err" } else { "out" }, &event.line);
                 if event.is_stderr {
                     stderr_buf.push_str(&event.line);
                     stderr_buf.push('\n');
@@ -457,15 +1753,20 @@ fn run_ssh_command_with_tail(
 
     let status = child.wait().context("waiting for ssh command")?;
     pb.finish_and_clear();
+    log.line("dsc", &format!("exited with {}", status));
 
     if !status.success() {
         return Err(anyhow!("ssh command failed for {}: {}", target, stderr_buf));
     }
 
+    stdout_buf.push_str(&stderr_buf);
     Ok(stdout_buf)
 }
 
-fn build_ssh_command(target: &str, extra_options: &[&str]) -> Result<std::process::Command> {
+pub(crate) fn build_ssh_command(
+    target: &str,
+    extra_options: &[&str],
+) -> Result<std::process::Command> {
     validate_ssh_target(target)?;
     let mut cmd = std::process::Command::new("ssh");
     cmd.arg("-o").arg("BatchMode=yes");
@@ -523,15 +1824,173 @@ fn stage(target: &str, message: &str) {
     println!("[{}] {}", target, message);
 }
 
-fn print_update_summary(discourse: &DiscourseConfig, metadata: &UpdateMetadata) -> String {
-    let payload = build_changelog_payload(metadata);
+fn print_update_summary(
+    discourse: &DiscourseConfig,
+    metadata: &UpdateMetadata,
+    log_dir: &Path,
+) -> Changelog {
+    let changelog = render_changelog(discourse, metadata);
     let discourse_label = colored_discourse_display(discourse);
     println!("\nUpdate summary for {}:", discourse_label);
-    for line in payload.lines() {
+    if changelog_category_id(discourse).is_some() {
+        println!("Title: {}", changelog.title);
+    }
+    for line in changelog.body.lines() {
         println!("{}", line);
     }
+    println!("Logs: {}", log_dir.display());
     println!();
-    payload
+    changelog
+}
+
+/// A rendered changelog: the post body, plus the title used when each update
+/// gets its own topic.
+struct Changelog {
+    title: String,
+    body: String,
+}
+
+const DEFAULT_CHANGELOG_TITLE: &str = "{{ name }} updated to {{ after_version }}";
+
+/// Render the changelog with the configured templates, falling back to the
+/// built-in format (with a warning) when a template cannot be read or rendered.
+fn render_changelog(discourse: &DiscourseConfig, metadata: &UpdateMetadata) -> Changelog {
+    let default = build_changelog_payload(metadata);
+    let settings = discourse.changelog.clone().unwrap_or_default();
+    let body_template = settings.template.as_ref().map(|path| {
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))
+    });
+    let title_template = settings.title.as_deref().unwrap_or(DEFAULT_CHANGELOG_TITLE);
+    let wants_plugins = title_template.contains("plugins")
+        || matches!(&body_template, Some(Ok(template)) if template.contains("plugins"));
+    let plugins = wants_plugins.then(|| {
+        installed_plugins(discourse).unwrap_or_else(|err| {
+            eprintln!(
+                "Warning: could not list plugins for {}: {:#}",
+                discourse.name, err
+            );
+            String::new()
+        })
+    });
+    let vars = changelog_vars(discourse, metadata, &default, plugins);
+
+    let body = match body_template
+        .map(|template| template.and_then(|template| render_template(&template, &vars)))
+    {
+        None => default,
+        Some(Ok(body)) => body,
+        Some(Err(err)) => {
+            eprintln!(
+                "Warning: changelog template for {} failed, using the built-in format: {:#}",
+                discourse.name, err
+            );
+            default
+        }
+    };
+    let title = render_template(title_template, &vars)
+        .or_else(|err| {
+            eprintln!(
+                "Warning: changelog title for {} failed, using the default: {:#}",
+                discourse.name, err
+            );
+            render_template(DEFAULT_CHANGELOG_TITLE, &vars)
+        })
+        .unwrap_or_default();
+    Changelog {
+        title: title.lines().next().unwrap_or_default().trim().to_string(),
+        body,
+    }
+}
+
+/// Placeholders available to changelog templates. Unknown values are empty
+/// and flags are `"true"` or empty, so both work with `{% if %}`.
+fn changelog_vars(
+    discourse: &DiscourseConfig,
+    metadata: &UpdateMetadata,
+    default: &str,
+    plugins: Option<String>,
+) -> TemplateVars {
+    let text = |value: &Option<String>| value.clone().unwrap_or_default();
+    let flag = |value: bool| if value { "true" } else { "" }.to_string();
+    let release_notes_url = match (
+        metadata.before_commit.as_deref(),
+        metadata.after_commit.as_deref(),
+    ) {
+        (Some(before), Some(after)) if before != after => format!(
+            "https://github.com/discourse/discourse/compare/{}...{}",
+            before, after
+        ),
+        _ => String::new(),
+    };
+    TemplateVars::from([
+        ("name", discourse.name.clone()),
+        (
+            "fullname",
+            discourse
+                .fullname
+                .clone()
+                .unwrap_or_else(|| discourse.name.clone()),
+        ),
+        (
+            "baseurl",
+            discourse.baseurl.trim_end_matches('/').to_string(),
+        ),
+        ("date", chrono::Local::now().format("%Y-%m-%d").to_string()),
+        ("before_version", text(&metadata.before_version)),
+        ("after_version", text(&metadata.after_version)),
+        ("after_version_error", text(&metadata.after_version_error)),
+        ("before_commit", text(&metadata.before_commit)),
+        ("after_commit", text(&metadata.after_commit)),
+        (
+            "before_commit_link",
+            format_commit_link(metadata.before_commit.as_deref()).unwrap_or_default(),
+        ),
+        (
+            "after_commit_link",
+            format_commit_link(metadata.after_commit.as_deref()).unwrap_or_default(),
+        ),
+        ("release_notes_url", release_notes_url),
+        ("os_version", text(&metadata.before_os_version)),
+        ("os_updated", flag(metadata.os_updated)),
+        ("rebooted", flag(metadata.server_rebooted)),
+        ("reclaimed_space", text(&metadata.reclaimed_space)),
+        ("root_disk_usage", text(&metadata.root_disk_usage)),
+        ("backup_filename", text(&metadata.backup_filename)),
+        ("smoke_checked", flag(metadata.smoke_checked)),
+        ("smoke_failure", text(&metadata.smoke_failure)),
+        ("rollback", text(&metadata.rollback)),
+        ("duration", format_duration(metadata.duration_secs)),
+        ("plugins", plugins.unwrap_or_default()),
+        ("default", default.to_string()),
+    ])
+}
+
+/// Installed plugins as `name version`, comma separated.
+fn installed_plugins(discourse: &DiscourseConfig) -> Result<String> {
+    let response = DiscourseClient::new(discourse)?.list_plugins()?;
+    let plugins = response
+        .get("plugins")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let names: Vec<String> = plugins
+        .iter()
+        .filter_map(|plugin| {
+            let name = plugin.get("name")?.as_str()?;
+            Some(match plugin.get("version").and_then(|v| v.as_str()) {
+                Some(version) => format!("{} {}", name, version),
+                None => name.to_string(),
+            })
+        })
+        .collect();
+    Ok(names.join(", "))
+}
+
+fn changelog_category_id(discourse: &DiscourseConfig) -> Option<u64> {
+    discourse
+        .changelog
+        .as_ref()
+        .and_then(|changelog| changelog.category_id)
 }
 
 fn discourse_display_name(discourse: &DiscourseConfig) -> String {
@@ -587,7 +2046,7 @@ fn get_root_disk_usage(target: &str) -> Result<String> {
     Ok(output.trim().to_string())
 }
 
-fn get_root_disk_available_gb(target: &str) -> Result<Option<u64>> {
+pub(crate) fn get_root_disk_available_gb(target: &str) -> Result<Option<u64>> {
     let cmd = "df -BG / | awk 'NR==2 {print $4}'";
     let output = run_ssh_command(target, cmd)?;
     let trimmed = output.trim();
@@ -598,6 +2057,420 @@ fn get_root_disk_available_gb(target: &str) -> Result<Option<u64>> {
     Ok(digits.parse::<u64>().ok())
 }
 
+/// Free space the root disk needs before updating: the recipe's
+/// `min_free_gb`, else `DSC_DISCOURSE_MIN_FREE_GB`, else 5.
+pub(crate) fn min_free_gb(recipe: &UpdateRecipe) -> u64 {
+    recipe
+        .min_free_gb
+        .or_else(|| {
+            std::env::var("DSC_DISCOURSE_MIN_FREE_GB")
+                .ok()
+                .and_then(|raw| raw.trim().parse::<u64>().ok())
+        })
+        .filter(|gb| *gb > 0)
+        .unwrap_or(5)
+}
+
+/// The steps used when a discourse has no recipe, or a recipe without `steps`.
+fn default_update_steps() -> Vec<UpdateStep> {
+    [
+        UpdateStepKind::OsUpdate,
+        UpdateStepKind::Reboot,
+        UpdateStepKind::Rebuild,
+        UpdateStepKind::Cleanup,
+    ]
+    .into_iter()
+    .map(UpdateStep::new)
+    .collect()
+}
+
+fn update_step_stage(step: &UpdateStep) -> UpdateStage {
+    match step.step {
+        UpdateStepKind::OsUpdate => UpdateStage::OsUpdated,
+        UpdateStepKind::Reboot => UpdateStage::Rebooted,
+        UpdateStepKind::Rebuild => UpdateStage::Rebuilt,
+        UpdateStepKind::Cleanup => UpdateStage::Cleaned,
+        UpdateStepKind::Command => UpdateStage::Command(update_step_label(step)),
+    }
+}
+
+fn update_step_label(step: &UpdateStep) -> String {
+    if let Some(name) = step.name.as_deref() {
+        return name.to_string();
+    }
+    match step.step {
+        UpdateStepKind::OsUpdate => "OS update",
+        UpdateStepKind::Reboot => "reboot",
+        UpdateStepKind::Rebuild => "Discourse rebuild",
+        UpdateStepKind::Cleanup => "cleanup",
+        UpdateStepKind::Command => "command",
+    }
+    .to_string()
+}
+
+/// The step's own command, else the environment/built-in default for its kind.
+fn update_step_command(step: &UpdateStep) -> Result<String> {
+    if let Some(command) = step.command.as_deref() {
+        return Ok(command.to_string());
+    }
+    let command = match step.step {
+        UpdateStepKind::OsUpdate => std::env::var("DSC_SSH_OS_UPDATE_CMD").unwrap_or_else(|_| {
+            "sudo -n DEBIAN_FRONTEND=noninteractive apt update && sudo -n DEBIAN_FRONTEND=noninteractive apt upgrade -y"
+                .to_string()
+        }),
+        UpdateStepKind::Reboot => {
+            std::env::var("DSC_SSH_REBOOT_CMD").unwrap_or_else(|_| "sudo -n reboot".to_string())
+        }
+        UpdateStepKind::Rebuild => std::env::var("DSC_SSH_UPDATE_CMD")
+            .unwrap_or_else(|_| "cd /var/discourse && sudo -n ./launcher rebuild app".to_string()),
+        UpdateStepKind::Cleanup => std::env::var("DSC_SSH_CLEANUP_CMD").unwrap_or_else(|_| {
+            // ./launcher cleanup runs docker container prune + docker image prune, both of which
+            // prompt for [y/N] confirmation. Without a TTY, they read EOF and default to N,
+            // silently doing nothing. Use -f to skip confirmation in non-interactive SSH.
+            "sudo -n docker container prune -f && sudo -n docker image prune -f".to_string()
+        }),
+        UpdateStepKind::Command => {
+            return Err(anyhow!(
+                "update step '{}' has no command",
+                update_step_label(step)
+            ));
+        }
+    };
+    Ok(command)
+}
+
+/// Where `./launcher` keeps container definitions on a standard install.
+const DISCOURSE_CONTAINERS_DIR: &str = "/var/discourse/containers";
+
+/// The part a container definition plays, judged by the templates it includes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum ContainerRole {
+    /// Postgres/Redis only (e.g. `data.yml`).
+    Data,
+    /// Neither web nor data templates (e.g. a mail receiver).
+    Other,
+    /// Web and data in one container (e.g. the default `app.yml`).
+    Standalone,
+    /// Web only, talking to a separate data container (e.g. `web_only.yml`).
+    Web,
+}
+
+/// The (container, command) pairs a rebuild step runs, in order. An explicit
+/// command (step or `DSC_SSH_UPDATE_CMD`) runs as-is; otherwise the containers
+/// are detected on the host, falling back to `rebuild app` if none are found
+/// (except in zero-downtime mode, which needs a web-only container).
+fn rebuild_plan(
+    target: &str,
+    discourse_label: &str,
+    step: &UpdateStep,
+    zero_downtime: bool,
+) -> Result<Vec<(Option<String>, String)>> {
+    if step.command.is_some() || std::env::var("DSC_SSH_UPDATE_CMD").is_ok() {
+        return Ok(vec![(None, update_step_command(step)?)]);
+    }
+    stage(discourse_label, "Detecting container definitions");
+    let containers = match run_ssh_command(target, &detect_containers_command()) {
+        Ok(output) => parse_container_roles(&output),
+        Err(err) => {
+            eprintln!(
+                "Warning: could not list {} on {}: {}",
+                DISCOURSE_CONTAINERS_DIR, target, err
+            );
+            Vec::new()
+        }
+    };
+    let containers = if zero_downtime {
+        let (web, skipped) = zero_downtime_containers(containers)?;
+        if !skipped.is_empty() {
+            stage(
+                discourse_label,
+                &format!(
+                    "Zero-downtime: leaving {} running (rebuild without --zero-downtime to update them)",
+                    skipped.join(", ")
+                ),
+            );
+        }
+        web
+    } else {
+        containers
+    };
+    if containers.is_empty() {
+        return Ok(vec![(None, update_step_command(step)?)]);
+    }
+    let names: Vec<&str> = containers.iter().map(|(name, _)| name.as_str()).collect();
+    stage(
+        discourse_label,
+        &format!("Rebuild order: {}", names.join(", ")),
+    );
+    Ok(containers
+        .into_iter()
+        .map(|(name, role)| {
+            let command = container_rebuild_command(&name, role, zero_downtime);
+            (Some(name), command)
+        })
+        .collect())
+}
+
+/// Container names with their roles, as detected on a host.
+pub(crate) type Containers = Vec<(String, ContainerRole)>;
+
+/// Split detected containers for a zero-downtime rebuild: the web-only ones,
+/// which can be swapped without downtime, and the names of the rest, which
+/// would take the site down (data) or cannot be swapped (standalone) and so
+/// are not rebuilt. Without a web-only container there is nothing to swap.
+fn zero_downtime_containers(containers: Containers) -> Result<(Containers, Vec<String>)> {
+    let (web, rest): (Vec<_>, Vec<_>) = containers
+        .into_iter()
+        .partition(|(_, role)| *role == ContainerRole::Web);
+    if web.is_empty() {
+        let found: Vec<&str> = rest.iter().map(|(name, _)| name.as_str()).collect();
+        return Err(anyhow!(
+            "--zero-downtime needs a web-only container, but none was found in {} (found: {}); update without --zero-downtime",
+            DISCOURSE_CONTAINERS_DIR,
+            if found.is_empty() {
+                "none".to_string()
+            } else {
+                found.join(", ")
+            }
+        ));
+    }
+    Ok((web, rest.into_iter().map(|(name, _)| name).collect()))
+}
+
+/// Print `<name> <web><data>` (flags 0/1) for each container definition,
+/// ignoring commented-out template lines.
+pub(crate) fn detect_containers_command() -> String {
+    format!(
+        "sudo -n sh -c 'cd {} 2>/dev/null || exit 0; for f in *.yml; do [ -f \"$f\" ] || continue; \
+         body=$(grep -v \"^[[:space:]]*#\" \"$f\"); w=0; d=0; \
+         echo \"$body\" | grep -q \"templates/web.template.yml\" && w=1; \
+         echo \"$body\" | grep -Eq \"templates/(postgres|redis)\" && d=1; \
+         echo \"${{f%.yml}} $w$d\"; done'",
+        DISCOURSE_CONTAINERS_DIR
+    )
+}
+
+/// Parse `detect_containers_command` output into containers in rebuild order:
+/// data first, web-only last.
+pub(crate) fn parse_container_roles(output: &str) -> Containers {
+    let mut containers: Containers = output
+        .lines()
+        .filter_map(|line| {
+            let (name, flags) = line.trim().split_once(' ')?;
+            let role = match flags.trim() {
+                "10" => ContainerRole::Web,
+                "11" => ContainerRole::Standalone,
+                "01" => ContainerRole::Data,
+                "00" => ContainerRole::Other,
+                _ => return None,
+            };
+            Some((name.to_string(), role))
+        })
+        .collect();
+    containers.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    containers
+}
+
+/// Web-only containers can be bootstrapped while the old one keeps serving;
+/// everything else needs a plain rebuild.
+fn container_rebuild_command(name: &str, role: ContainerRole, zero_downtime: bool) -> String {
+    if zero_downtime && role == ContainerRole::Web {
+        format!(
+            "cd /var/discourse && sudo -n ./launcher bootstrap {name} && sudo -n ./launcher destroy {name} && sudo -n ./launcher start {name}"
+        )
+    } else {
+        format!("cd /var/discourse && sudo -n ./launcher rebuild {name}")
+    }
+}
+
+/// Containers running the Discourse app: web-only ones first, then
+/// standalone ones; `app` when nothing is detected.
+pub(crate) fn app_containers(target: &str) -> Vec<String> {
+    let containers = run_ssh_command(target, &detect_containers_command())
+        .map(|output| parse_container_roles(&output))
+        .unwrap_or_default();
+    let mut names: Vec<String> = [ContainerRole::Web, ContainerRole::Standalone]
+        .iter()
+        .flat_map(|role| {
+            containers
+                .iter()
+                .filter(move |(_, container_role)| container_role == role)
+                .map(|(name, _)| name.clone())
+        })
+        .collect();
+    if names.is_empty() {
+        names.push("app".to_string());
+    }
+    names
+}
+
+/// Tag the current image of each app container as `dsc-previous` so a failed
+/// smoke check can go back to it. Returns the containers that were tagged.
+fn snapshot_app_images(target: &str, discourse_label: &str) -> Vec<String> {
+    stage(discourse_label, "Saving current images for rollback");
+    app_containers(target)
+        .into_iter()
+        .filter(|name| {
+            let command = format!(
+                "sudo -n docker tag local_discourse/{name} local_discourse/{name}:{PREVIOUS_IMAGE_TAG}"
+            );
+            match run_ssh_command(target, &command) {
+                Ok(_) => true,
+                Err(err) => {
+                    eprintln!(
+                        "Warning: could not save the image of {} on {}: {}",
+                        name, target, err
+                    );
+                    false
+                }
+            }
+        })
+        .collect()
+}
+
+const PREVIOUS_IMAGE_TAG: &str = "dsc-previous";
+
+/// Undo a rebuild that failed its smoke checks: the rebuild step's `rollback`
+/// command if it has one, else restart the app containers from the saved
+/// images. Returns a line for the summary.
+fn roll_back_rebuild(
+    target: &str,
+    discourse_label: &str,
+    step: &UpdateStep,
+    snapshots: &[String],
+    before: &VersionInfo,
+    log_dir: &Path,
+) -> String {
+    stage(discourse_label, "Rolling back the rebuild");
+    let run_logged = |command: &str| {
+        let mut log = StageLog::open(log_dir, "rebuild rollback", command);
+        run_ssh_command_logged(target, command, &mut log)
+    };
+    let result = if let Some(command) = step.rollback.as_deref() {
+        run_logged(&expand_rollback_command(command, before))
+            .map(|_| "Rolled back with the rebuild rollback command".to_string())
+    } else if snapshots.is_empty() {
+        return "Rollback skipped: no previous image was saved".to_string();
+    } else {
+        snapshots
+            .iter()
+            .try_for_each(|name| {
+                let command = format!(
+                    "sudo -n docker tag local_discourse/{name}:{PREVIOUS_IMAGE_TAG} local_discourse/{name}:latest && cd /var/discourse && sudo -n ./launcher destroy {name} && sudo -n ./launcher start {name}"
+                );
+                run_logged(&command).map(|_| ())
+            })
+            .map(|_| format!("Rolled back to the previous image ({})", snapshots.join(", ")))
+    };
+    let note =
+        result.unwrap_or_else(|err| format!("Rollback failed: {}", err.to_string().trim_end()));
+    stage(discourse_label, &note);
+    note
+}
+
+/// Fill `{before_version}`/`{before_commit}` in a rollback command, e.g. to
+/// rebuild at the git ref that was running before the update.
+fn expand_rollback_command(command: &str, before: &VersionInfo) -> String {
+    command
+        .replace(
+            "{before_version}",
+            before.version.as_deref().unwrap_or_default(),
+        )
+        .replace(
+            "{before_commit}",
+            before.commit.as_deref().unwrap_or_default(),
+        )
+}
+
+/// Default smoke check paths: homepage, a JSON route and the status probe.
+const SMOKE_CHECK_PATHS: &[&str] = &["/", "/latest.json", "/srv/status"];
+
+/// Default rebuild log lines that mean a plugin (or the app) failed to load.
+const SMOKE_LOG_PATTERNS: &[&str] = &[
+    "rake aborted!",
+    "LoadError",
+    "NameError",
+    "NoMethodError",
+    "SyntaxError",
+    "uninitialized constant",
+    "is not compatible with",
+];
+
+/// Check the configured pages and API path, then scan the rebuild log.
+fn run_smoke_checks(
+    discourse: &DiscourseConfig,
+    client: &DiscourseClient,
+    smoke: &SmokeChecks,
+    rebuild_log: &str,
+) -> Result<()> {
+    let mut failures = Vec::new();
+    let paths = smoke
+        .paths
+        .clone()
+        .unwrap_or_else(|| SMOKE_CHECK_PATHS.iter().map(|p| p.to_string()).collect());
+    for path in &paths {
+        if let Err(err) = check_page_with_retry(client, path, 3) {
+            failures.push(format!("{:#}", err));
+        }
+    }
+    if let Some(path) = smoke.api_path.as_deref() {
+        let checked =
+            ensure_api_credentials(discourse).and_then(|_| check_page_with_retry(client, path, 3));
+        if let Err(err) = checked {
+            failures.push(format!("{:#}", err));
+        }
+    }
+    let patterns = smoke
+        .log_patterns
+        .clone()
+        .unwrap_or_else(|| SMOKE_LOG_PATTERNS.iter().map(|p| p.to_string()).collect());
+    for line in scan_rebuild_log(rebuild_log, &patterns) {
+        failures.push(format!("rebuild log: {}", line));
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(failures.join("; ")))
+    }
+}
+
+/// Up to three rebuild log lines matching any of `patterns`.
+fn scan_rebuild_log(log: &str, patterns: &[String]) -> Vec<String> {
+    strip_ansi_codes(log)
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            patterns
+                .iter()
+                .any(|pattern| line.contains(pattern.as_str()))
+        })
+        .take(3)
+        .map(str::to_string)
+        .collect()
+}
+
+fn check_page_with_retry(client: &DiscourseClient, path: &str, attempts: usize) -> Result<()> {
+    let total = attempts.max(1);
+    let mut attempt = 0;
+    loop {
+        match client.check_page(path) {
+            Ok(()) => return Ok(()),
+            Err(err) if attempt + 1 >= total => return Err(err),
+            Err(_) => {
+                attempt += 1;
+                std::thread::sleep(Duration::from_secs(2 * attempt as u64));
+            }
+        }
+    }
+}
+
+fn update_step_rollback(step: &UpdateStep) -> Option<String> {
+    step.rollback.clone().or_else(|| match step.step {
+        UpdateStepKind::OsUpdate => os_update_rollback_cmd(),
+        _ => None,
+    })
+}
+
 fn os_update_rollback_cmd() -> Option<String> {
     let raw = std::env::var("DSC_SSH_OS_UPDATE_ROLLBACK_CMD").unwrap_or_default();
     let trimmed = raw.trim();
@@ -618,6 +2491,9 @@ fn build_changelog_payload(metadata: &UpdateMetadata) -> String {
     let after_commit = format_commit_link(metadata.after_commit.as_deref());
 
     let mut body = Vec::new();
+    if let Some(filename) = metadata.backup_filename.as_deref() {
+        body.push(format!("- [x] Backup created: {}", filename));
+    }
     if metadata.os_updated {
         body.push(format!("- [x] OS updated {}", os_version));
     } else {
@@ -662,6 +2538,14 @@ fn build_changelog_payload(metadata: &UpdateMetadata) -> String {
         ));
     }
     body.push(format!("- [x] Root disk usage (df -h /): {}", root_disk));
+    if let Some(failure) = metadata.smoke_failure.as_deref() {
+        body.push(format!("- [ ] Smoke checks failed: {}", failure));
+        if let Some(rollback) = metadata.rollback.as_deref() {
+            body.push(format!("  - {}", rollback));
+        }
+    } else if metadata.smoke_checked {
+        body.push("- [x] Smoke checks passed".to_string());
+    }
     let test_marker = std::env::var("DSC_TEST_MARKER").ok();
     if let Some(marker) = &test_marker {
         body.push(format!("- Run-ID: {}", marker));
@@ -728,7 +2612,18 @@ fn strip_ansi_codes(input: &str) -> String {
     out
 }
 
-fn post_changelog_update(discourse: &DiscourseConfig, payload: &str) -> Result<u64> {
+/// Post the changelog as a new topic in `changelog.category_id` when set,
+/// else as a reply in `changelog_topic_id`. Returns the new post's URL.
+fn post_changelog_update(discourse: &DiscourseConfig, changelog: &Changelog) -> Result<String> {
+    let base = discourse.baseurl.trim_end_matches('/');
+    if let Some(category_id) = changelog_category_id(discourse) {
+        let client = DiscourseClient::new(discourse)?;
+        let topic_id = client.create_topic(category_id, &changelog.title, &changelog.body)?;
+        if std::env::var("DSC_TEST_MARKER").is_ok() {
+            println!("DSC_TEST_TOPIC_ID={}", topic_id);
+        }
+        return Ok(format!("{}/t/{}", base, topic_id));
+    }
     let topic_id = discourse.changelog_topic_id.ok_or_else(|| {
         missing_config(
             "changelog_topic_id",
@@ -737,11 +2632,11 @@ fn post_changelog_update(discourse: &DiscourseConfig, payload: &str) -> Result<u
         )
     })?;
     let client = DiscourseClient::new(discourse)?;
-    let post_id = client.create_post(topic_id, payload)?;
+    let post_id = client.create_post(topic_id, &changelog.body)?;
     if std::env::var("DSC_TEST_MARKER").is_ok() {
         println!("DSC_TEST_POST_ID={}", post_id);
     }
-    Ok(post_id)
+    Ok(format!("{}/p/{}", base, post_id))
 }
 
 fn confirm_changelog_post(yes: bool) -> Result<bool> {
@@ -760,31 +2655,54 @@ fn confirm_changelog_post(yes: bool) -> Result<bool> {
     Ok(matches!(input.trim(), "y" | "Y" | "yes" | "YES"))
 }
 
-fn handle_changelog_post(discourse: &DiscourseConfig, payload: &str, yes: bool) -> Result<()> {
-    let topic_id = discourse.changelog_topic_id;
-    if topic_id.is_none() {
+/// Post the changelog unless a resumed run already did; records the stage once posted.
+fn post_changelog_once(
+    discourse: &DiscourseConfig,
+    changelog: &Changelog,
+    yes: bool,
+    tracker: &RunTracker,
+) -> Result<()> {
+    if tracker
+        .progress(&discourse.name)
+        .has(&UpdateStage::ChangelogPosted)
+    {
+        println!("Changelog post skipped: already posted in this run");
+        return Ok(());
+    }
+    if handle_changelog_post(discourse, changelog, yes)? {
+        tracker.complete(&discourse.name, UpdateStage::ChangelogPosted);
+    }
+    Ok(())
+}
+
+/// Returns whether a post was actually created.
+fn handle_changelog_post(
+    discourse: &DiscourseConfig,
+    changelog: &Changelog,
+    yes: bool,
+) -> Result<bool> {
+    if discourse.changelog_topic_id.is_none() && changelog_category_id(discourse).is_none() {
         println!(
-            "Changelog post skipped: missing changelog_topic_id for {}",
+            "Changelog post skipped: missing changelog_topic_id or changelog category_id for {}",
             discourse.name
         );
-        return Ok(());
+        return Ok(false);
     }
 
     if let Err(err) = ensure_api_credentials(discourse) {
         println!("Changelog post skipped: {}", err);
-        return Ok(());
+        return Ok(false);
     }
 
     if !confirm_changelog_post(yes)? {
         println!("Changelog post skipped.");
-        return Ok(());
+        return Ok(false);
     }
 
-    match post_changelog_update(discourse, payload) {
-        Ok(post_id) => {
-            let base = discourse.baseurl.trim_end_matches('/');
-            println!("Changelog post created: {}/p/{}", base, post_id);
-            Ok(())
+    match post_changelog_update(discourse, changelog) {
+        Ok(url) => {
+            println!("Changelog post created: {}", url);
+            Ok(true)
         }
         Err(err) => {
             println!("Changelog post failed: {}", err);
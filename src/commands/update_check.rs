@@ -0,0 +1,357 @@
+use crate::api::{DiscourseClient, UpstreamClient};
+use crate::commands::update::{
+    align_columns, app_containers, get_root_disk_available_gb, min_free_gb, parallel_worker_count,
+    run_ssh_command,
+};
+use crate::config::DiscourseConfig;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::thread;
+
+/// Packages whose upgrade needs a reboot to take effect.
+const REBOOT_PACKAGE_PREFIXES: &[&str] = &[
+    "linux-image",
+    "linux-generic",
+    "linux-modules",
+    "linux-firmware",
+    "libc6",
+    "systemd",
+    "dbus",
+];
+
+/// Printed by the default apt check when `/var/run/reboot-required` exists.
+const REBOOT_REQUIRED_MARKER: &str = "*** System restart required ***";
+
+/// What `dsc update --check` found for one install.
+#[derive(Debug, Default, Serialize)]
+pub struct UpdateCheck {
+    pub name: String,
+    pub current_version: Option<String>,
+    pub current_commit: Option<String>,
+    pub latest_version: Option<String>,
+    pub commits_behind: Option<u64>,
+    pub pending_upgrades: Option<Vec<String>>,
+    pub reboot_required: Option<bool>,
+    pub disk_free_gb: Option<u64>,
+    pub min_free_gb: u64,
+    pub outdated_plugins: Option<Vec<String>>,
+    pub errors: Vec<String>,
+}
+
+impl UpdateCheck {
+    fn note_error(&mut self, what: &str, err: anyhow::Error) {
+        self.errors
+            .push(format!("{}: {:#}", what, err).trim_end().to_string());
+    }
+
+    fn disk_low(&self) -> bool {
+        self.disk_free_gb
+            .is_some_and(|free_gb| free_gb < self.min_free_gb)
+    }
+}
+
+/// Report what an update would do for `targets`, without changing anything.
+pub fn update_check(
+    targets: &[DiscourseConfig],
+    parallel: bool,
+    max: Option<usize>,
+    report: Option<&Path>,
+) -> Result<()> {
+    let upstream = UpstreamClient::from_env()?;
+    let latest_version = match upstream.latest_version() {
+        Ok(version) => version,
+        Err(err) => {
+            eprintln!(
+                "Warning: could not read the latest Discourse version at {}: {:#}",
+                upstream.reference(),
+                err
+            );
+            None
+        }
+    };
+
+    let checks = if parallel {
+        let mut checks = Vec::new();
+        let max_threads = parallel_worker_count(max, targets.len());
+        let mut handles: VecDeque<(String, thread::JoinHandle<UpdateCheck>)> = VecDeque::new();
+        for discourse in targets {
+            if handles.len() >= max_threads
+                && let Some((name, handle)) = handles.pop_front()
+            {
+                checks.push(join_check(&name, handle));
+            }
+            let discourse = discourse.clone();
+            let upstream = upstream.clone();
+            let latest_version = latest_version.clone();
+            let name = discourse.name.clone();
+            handles.push_back((
+                name,
+                thread::spawn(move || check_install(&discourse, &upstream, latest_version)),
+            ));
+        }
+        for (name, handle) in handles {
+            checks.push(join_check(&name, handle));
+        }
+        checks
+    } else {
+        targets
+            .iter()
+            .map(|discourse| check_install(discourse, &upstream, latest_version.clone()))
+            .collect()
+    };
+
+    println!("Update check (upstream: {}):", upstream.reference());
+    print!("{}", render_update_check_text(&checks));
+    if let Some(path) = report {
+        let raw = if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+            serde_json::to_string_pretty(&checks)?
+        } else {
+            render_update_check_markdown(&checks)
+        };
+        std::fs::write(path, raw).with_context(|| format!("writing {}", path.display()))?;
+        println!("Report written to {}", path.display());
+    }
+    Ok(())
+}
+
+fn join_check(name: &str, handle: thread::JoinHandle<UpdateCheck>) -> UpdateCheck {
+    handle.join().unwrap_or_else(|_| UpdateCheck {
+        name: name.to_string(),
+        errors: vec!["check thread panicked".to_string()],
+        ..UpdateCheck::default()
+    })
+}
+
+fn check_install(
+    discourse: &DiscourseConfig,
+    upstream: &UpstreamClient,
+    latest_version: Option<String>,
+) -> UpdateCheck {
+    eprintln!("Checking {}", discourse.name);
+    let target = discourse
+        .ssh_host
+        .clone()
+        .unwrap_or_else(|| discourse.name.clone());
+    let mut check = UpdateCheck {
+        name: discourse.name.clone(),
+        latest_version,
+        min_free_gb: min_free_gb(&discourse.update.clone().unwrap_or_default()),
+        ..UpdateCheck::default()
+    };
+
+    match DiscourseClient::new(discourse).and_then(|client| client.fetch_version_info()) {
+        Ok(info) => {
+            check.current_version = info.version;
+            check.current_commit = info.commit;
+        }
+        Err(err) => check.note_error("version", err),
+    }
+    if let Some(commit) = check.current_commit.as_deref() {
+        match upstream.commits_behind(commit) {
+            Ok(behind) => check.commits_behind = Some(behind),
+            Err(err) => check.note_error("commits behind", err),
+        }
+    }
+
+    let apt_cmd = std::env::var("DSC_SSH_APT_CHECK_CMD").unwrap_or_else(|_| {
+        format!(
+            "apt list --upgradable 2>/dev/null; if [ -f /var/run/reboot-required ]; then echo '{}'; fi",
+            REBOOT_REQUIRED_MARKER
+        )
+    });
+    match run_ssh_command(&target, &apt_cmd) {
+        Ok(output) => {
+            let (packages, reboot_required) = parse_apt_upgradable(&output);
+            check.pending_upgrades = Some(packages);
+            check.reboot_required = Some(reboot_required);
+        }
+        Err(err) => check.note_error("apt", err),
+    }
+
+    match get_root_disk_available_gb(&target) {
+        Ok(free_gb) => check.disk_free_gb = free_gb,
+        Err(err) => check.note_error("disk", err),
+    }
+
+    let plugin_cmd = std::env::var("DSC_SSH_PLUGIN_CHECK_CMD")
+        .unwrap_or_else(|_| plugin_check_command(&app_containers(&target)[0]));
+    match run_ssh_command(&target, &plugin_cmd) {
+        Ok(output) => check.outdated_plugins = Some(parse_plugin_heads(&output)),
+        Err(err) => check.note_error("plugins", err),
+    }
+    check
+}
+
+/// Print `<plugin> <local HEAD> <remote HEAD>` for each git-managed plugin in
+/// `container`, using `git ls-remote` so nothing is fetched.
+fn plugin_check_command(container: &str) -> String {
+    format!(
+        "sudo -n docker exec -u discourse -w /var/www/discourse/plugins {} sh -c '\
+         for d in */; do [ -d \"$d.git\" ] || continue; \
+         b=$(git -C \"$d\" rev-parse --abbrev-ref HEAD); \
+         l=$(git -C \"$d\" rev-parse HEAD); \
+         r=$(git -C \"$d\" ls-remote origin \"refs/heads/$b\" | cut -f1); \
+         echo \"${{d%/}} $l $r\"; done'",
+        container
+    )
+}
+
+/// Parse `apt list --upgradable` output into package names, and whether a
+/// reboot is (or will be) required.
+fn parse_apt_upgradable(output: &str) -> (Vec<String>, bool) {
+    let mut reboot_required = false;
+    let mut packages = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if line == REBOOT_REQUIRED_MARKER {
+            reboot_required = true;
+        } else if line.contains("[upgradable from")
+            && let Some((name, _)) = line.split_once('/')
+        {
+            reboot_required |= REBOOT_PACKAGE_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix));
+            packages.push(name.to_string());
+        }
+    }
+    (packages, reboot_required)
+}
+
+/// Plugins whose local HEAD differs from their remote branch head. Plugins
+/// without a remote head (detached, or `ls-remote` failed) are left out.
+fn parse_plugin_heads(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let (name, local, remote) = (parts.next()?, parts.next()?, parts.next()?);
+            (local != remote).then(|| name.to_string())
+        })
+        .collect()
+}
+
+fn update_check_row(check: &UpdateCheck) -> [String; 7] {
+    let current = check.current_version.as_deref().unwrap_or("unknown");
+    let version = match check.latest_version.as_deref() {
+        Some(latest) if latest != current => format!("{} -> {}", current, latest),
+        _ => current.to_string(),
+    };
+    let unknown = || "unknown".to_string();
+    [
+        check.name.clone(),
+        version,
+        check
+            .commits_behind
+            .map(|behind| behind.to_string())
+            .unwrap_or_else(unknown),
+        check
+            .pending_upgrades
+            .as_ref()
+            .map(|packages| packages.len().to_string())
+            .unwrap_or_else(unknown),
+        check
+            .reboot_required
+            .map(|reboot| if reboot { "yes" } else { "no" }.to_string())
+            .unwrap_or_else(unknown),
+        match check.disk_free_gb {
+            Some(free_gb) if check.disk_low() => {
+                format!("{}G (LOW, min {}G)", free_gb, check.min_free_gb)
+            }
+            Some(free_gb) => format!("{}G", free_gb),
+            None => unknown(),
+        },
+        check
+            .outdated_plugins
+            .as_ref()
+            .map(|plugins| plugins.len().to_string())
+            .unwrap_or_else(unknown),
+    ]
+}
+
+const UPDATE_CHECK_HEADERS: [&str; 7] = [
+    "Name",
+    "Version",
+    "Behind",
+    "Apt",
+    "Reboot",
+    "Disk free",
+    "Plugins",
+];
+
+fn update_check_details(check: &UpdateCheck) -> Vec<String> {
+    let mut details = Vec::new();
+    if let Some(plugins) = check.outdated_plugins.as_ref().filter(|p| !p.is_empty()) {
+        details.push(format!("plugins with new commits: {}", plugins.join(", ")));
+    }
+    details.extend(check.errors.iter().cloned());
+    details
+}
+
+fn render_update_check_text(checks: &[UpdateCheck]) -> String {
+    let rows: Vec<[String; 7]> = checks.iter().map(update_check_row).collect();
+    let mut out = align_columns(UPDATE_CHECK_HEADERS, &rows);
+    for check in checks {
+        for detail in update_check_details(check) {
+            out.push_str(&format!("{}: {}\n", check.name, detail));
+        }
+    }
+    out
+}
+
+fn render_update_check_markdown(checks: &[UpdateCheck]) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("| {} |\n", UPDATE_CHECK_HEADERS.join(" | ")));
+    out.push_str(&format!(
+        "|{}\n",
+        " --- |".repeat(UPDATE_CHECK_HEADERS.len())
+    ));
+    for check in checks {
+        out.push_str(&format!("| {} |\n", update_check_row(check).join(" | ")));
+    }
+    for check in checks {
+        for detail in update_check_details(check) {
+            out.push_str(&format!(
+                "\n- {}: {}",
+                check.name,
+                detail.replace('|', "\\|")
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{REBOOT_REQUIRED_MARKER, parse_apt_upgradable, parse_plugin_heads};
+
+    #[test]
+    fn apt_upgrades_flag_kernel_and_pending_reboots() {
+        let output = "Listing...\n\
+            curl/noble-updates 8.5.0-2ubuntu10.6 amd64 [upgradable from: 8.5.0-2ubuntu10.5]\n";
+        let (packages, reboot) = parse_apt_upgradable(output);
+        assert_eq!(packages, ["curl"]);
+        assert!(!reboot);
+
+        let output = format!(
+            "{}linux-image-generic/noble-updates 6.8.0.50.50 amd64 [upgradable from: 6.8.0.49.49]\n",
+            output
+        );
+        let (packages, reboot) = parse_apt_upgradable(&output);
+        assert_eq!(packages, ["curl", "linux-image-generic"]);
+        assert!(reboot);
+
+        let (packages, reboot) = parse_apt_upgradable(REBOOT_REQUIRED_MARKER);
+        assert!(packages.is_empty());
+        assert!(reboot);
+    }
+
+    #[test]
+    fn plugins_with_moved_remote_heads_are_outdated() {
+        let output = "docker_manager abc123 abc123\n\
+            discourse-solved abc123 def456\n\
+            detached-plugin abc123\n";
+        assert_eq!(parse_plugin_heads(output), ["discourse-solved"]);
+    }
+}
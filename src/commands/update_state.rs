@@ -0,0 +1,499 @@
+use crate::commands::update::WaveOptions;
+use crate::utils::slugify;
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A completed step of the update workflow, recorded so `--resume` can skip it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStage {
+    DiskChecked,
+    BackedUp,
+    OsUpdated,
+    Rebooted,
+    Rebuilt,
+    Cleaned,
+    ChangelogPosted,
+    /// A named `command` step from an update recipe.
+    Command(String),
+    /// One container of a multi-container rebuild.
+    ContainerRebuilt(String),
+}
+
+impl UpdateStage {
+    pub fn label(&self) -> String {
+        match self {
+            UpdateStage::DiskChecked => "disk checked".to_string(),
+            UpdateStage::BackedUp => "backed up".to_string(),
+            UpdateStage::OsUpdated => "OS updated".to_string(),
+            UpdateStage::Rebooted => "rebooted".to_string(),
+            UpdateStage::Rebuilt => "rebuilt".to_string(),
+            UpdateStage::Cleaned => "cleaned".to_string(),
+            UpdateStage::ChangelogPosted => "changelog posted".to_string(),
+            UpdateStage::Command(name) => name.clone(),
+            UpdateStage::ContainerRebuilt(name) => format!("rebuilt {}", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallStatus {
+    Pending,
+    Running,
+    Ok,
+    Failed,
+}
+
+impl InstallStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            InstallStatus::Pending => "pending",
+            InstallStatus::Running => "running",
+            InstallStatus::Ok => "ok",
+            InstallStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Progress of one install within an update run, plus the details gathered
+/// by stages that a resumed run will skip.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallProgress {
+    pub name: String,
+    pub status: InstallStatus,
+    #[serde(default)]
+    pub stages: Vec<UpdateStage>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub before_version: Option<String>,
+    #[serde(default)]
+    pub before_commit: Option<String>,
+    #[serde(default)]
+    pub before_os_version: Option<String>,
+    #[serde(default)]
+    pub backup_filename: Option<String>,
+    #[serde(default)]
+    pub reclaimed_space: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl InstallProgress {
+    fn new(name: &str) -> Self {
+        InstallProgress {
+            name: name.to_string(),
+            status: InstallStatus::Pending,
+            stages: Vec::new(),
+            error: None,
+            before_version: None,
+            before_commit: None,
+            before_os_version: None,
+            backup_filename: None,
+            reclaimed_space: None,
+            updated_at: None,
+        }
+    }
+
+    pub fn has(&self, stage: &UpdateStage) -> bool {
+        self.stages.contains(stage)
+    }
+
+    pub fn complete(&mut self, stage: UpdateStage) {
+        if !self.has(&stage) {
+            self.stages.push(stage);
+        }
+    }
+}
+
+/// One `dsc update` invocation, stored as `<state dir>/<id>.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRun {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    #[serde(default)]
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub post_changelog: bool,
+    #[serde(default)]
+    pub backup_first: bool,
+    #[serde(default)]
+    pub zero_downtime: bool,
+    #[serde(default)]
+    pub health_check: bool,
+    /// Canary/batch settings of a `--waves` rollout.
+    #[serde(default)]
+    pub waves: Option<WaveOptions>,
+    pub installs: Vec<InstallProgress>,
+}
+
+impl UpdateRun {
+    pub fn count(&self, status: InstallStatus) -> usize {
+        self.installs
+            .iter()
+            .filter(|install| install.status == status)
+            .count()
+    }
+
+    /// Mark the run in progress again, queueing failed installs for a retry
+    /// while keeping their completed stages.
+    fn reopen(&mut self) {
+        self.finished_at = None;
+        for install in &mut self.installs {
+            if install.status != InstallStatus::Ok {
+                install.status = InstallStatus::Pending;
+                install.error = None;
+            }
+        }
+    }
+
+    /// Installs a resumed run still has to (re)do.
+    pub fn unfinished(&self) -> Vec<String> {
+        self.installs
+            .iter()
+            .filter(|install| install.status != InstallStatus::Ok)
+            .map(|install| install.name.clone())
+            .collect()
+    }
+}
+
+/// Shared handle on the run being executed; every change is written through
+/// to the state file so an interrupted run can be resumed.
+#[derive(Debug, Clone)]
+pub struct RunTracker {
+    path: PathBuf,
+    run: Arc<Mutex<UpdateRun>>,
+}
+
+impl RunTracker {
+    /// Start a new run for the given installs and write its state file.
+    pub fn start(
+        names: &[String],
+        post_changelog: bool,
+        backup_first: bool,
+        zero_downtime: bool,
+        health_check: bool,
+        waves: Option<WaveOptions>,
+    ) -> Result<Self> {
+        let dir = update_state_dir();
+        let started_at = Utc::now();
+        let stamp = started_at.format("%Y%m%d-%H%M%S").to_string();
+        let mut id = stamp.clone();
+        let mut suffix = 2;
+        while dir.join(format!("{}.json", id)).exists() {
+            id = format!("{}-{}", stamp, suffix);
+            suffix += 1;
+        }
+        let run = UpdateRun {
+            id: id.clone(),
+            started_at,
+            finished_at: None,
+            post_changelog,
+            backup_first,
+            zero_downtime,
+            health_check,
+            waves,
+            installs: names
+                .iter()
+                .map(|name| InstallProgress::new(name))
+                .collect(),
+        };
+        let tracker = RunTracker {
+            path: dir.join(format!("{}.json", id)),
+            run: Arc::new(Mutex::new(run)),
+        };
+        if !names.is_empty() {
+            tracker.save(&tracker.lock())?;
+        }
+        Ok(tracker)
+    }
+
+    /// Reopen a run (the latest one with unfinished installs when `id` is None)
+    /// and reset its failed installs so they are retried.
+    pub fn resume(id: Option<&str>) -> Result<Self> {
+        let mut run = match id {
+            Some(id) => load_run(id)?,
+            None => list_runs()?
+                .into_iter()
+                .rev()
+                .find(|run| !run.unfinished().is_empty())
+                .ok_or_else(|| anyhow!("no update run to resume"))?,
+        };
+        if run.unfinished().is_empty() {
+            return Err(anyhow!("update run {} has nothing left to resume", run.id));
+        }
+        run.reopen();
+        let tracker = RunTracker {
+            path: update_state_dir().join(format!("{}.json", run.id)),
+            run: Arc::new(Mutex::new(run)),
+        };
+        tracker.save(&tracker.lock())?;
+        Ok(tracker)
+    }
+
+    pub fn id(&self) -> String {
+        self.lock().id.clone()
+    }
+
+    pub fn snapshot(&self) -> UpdateRun {
+        self.lock().clone()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Directory holding this run's stage logs, one subdirectory per install.
+    pub fn log_root(&self) -> PathBuf {
+        self.path.with_extension("")
+    }
+
+    /// Where one install's stage logs for this run are written.
+    pub fn log_dir(&self, name: &str) -> PathBuf {
+        self.log_root().join(name)
+    }
+
+    /// Current progress of one install (a fresh entry if it is not part of the run).
+    pub fn progress(&self, name: &str) -> InstallProgress {
+        self.lock()
+            .installs
+            .iter()
+            .find(|install| install.name == name)
+            .cloned()
+            .unwrap_or_else(|| InstallProgress::new(name))
+    }
+
+    /// Apply a change to one install and persist it. Write failures only warn,
+    /// so a full disk or read-only state dir does not abort an update midway.
+    pub fn record(&self, name: &str, change: impl FnOnce(&mut InstallProgress)) {
+        let mut run = self.lock();
+        let Some(install) = run.installs.iter_mut().find(|install| install.name == name) else {
+            return;
+        };
+        change(install);
+        install.updated_at = Some(Utc::now());
+        if let Err(err) = self.save(&run) {
+            eprintln!("Warning: could not save update state: {:#}", err);
+        }
+    }
+
+    pub fn complete(&self, name: &str, stage: UpdateStage) {
+        self.record(name, |install| install.complete(stage));
+    }
+
+    pub fn finish(&self) {
+        let mut run = self.lock();
+        if run.installs.is_empty() {
+            return;
+        }
+        run.finished_at = Some(Utc::now());
+        if let Err(err) = self.save(&run) {
+            eprintln!("Warning: could not save update state: {:#}", err);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, UpdateRun> {
+        self.run
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn save(&self, run: &UpdateRun) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("creating {}", parent.display()))?;
+        }
+        let raw = serde_json::to_string_pretty(run)?;
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, raw).with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("writing {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+/// Directory holding update run state.
+///
+/// `DSC_UPDATE_STATE_DIR` wins, then `$XDG_STATE_HOME/dsc/updates`, then
+/// `~/.local/state/dsc/updates`.
+pub fn update_state_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("DSC_UPDATE_STATE_DIR")
+        && !dir.trim().is_empty()
+    {
+        return PathBuf::from(dir);
+    }
+    if let Some(state_home) = std::env::var_os("XDG_STATE_HOME") {
+        return PathBuf::from(state_home).join("dsc").join("updates");
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        return PathBuf::from(home)
+            .join(".local")
+            .join("state")
+            .join("dsc")
+            .join("updates");
+    }
+    PathBuf::from(".dsc").join("updates")
+}
+
+/// Stage log directory of one install in a recorded run.
+pub fn run_log_dir(run_id: &str, name: &str) -> PathBuf {
+    update_state_dir().join(run_id).join(name)
+}
+
+/// Timestamped output of one SSH stage, written to `<log dir>/<NN>-<stage>.log`.
+/// Logging problems only warn; they never fail an update.
+pub struct StageLog {
+    file: Option<File>,
+}
+
+impl StageLog {
+    /// Start the next numbered log file in `dir`, headed by the command.
+    pub fn open(dir: &Path, stage: &str, command: &str) -> Self {
+        let opened = std::fs::create_dir_all(dir)
+            .and_then(|_| std::fs::read_dir(dir))
+            .and_then(|entries| {
+                let path = dir.join(format!("{:02}-{}.log", entries.count() + 1, slugify(stage)));
+                File::create(path)
+            });
+        let mut log = match opened {
+            Ok(file) => StageLog { file: Some(file) },
+            Err(err) => {
+                eprintln!(
+                    "Warning: could not write update log in {}: {}",
+                    dir.display(),
+                    err
+                );
+                StageLog { file: None }
+            }
+        };
+        log.line("cmd", command);
+        log
+    }
+
+    /// Append one line, tagged with the time and its stream (`out`/`err`/...).
+    pub fn line(&mut self, stream: &str, line: &str) {
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        let stamp = Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ");
+        if writeln!(file, "{} {} | {}", stamp, stream, line).is_err() {
+            self.file = None;
+        }
+    }
+
+    /// Append every line of captured output.
+    pub fn lines(&mut self, stream: &str, output: &str) {
+        for line in output.lines() {
+            self.line(stream, line);
+        }
+    }
+}
+
+pub fn load_run(id: &str) -> Result<UpdateRun> {
+    let path = update_state_dir().join(format!("{}.json", id));
+    if !path.exists() {
+        return Err(anyhow!("update run not found: {}", id));
+    }
+    read_run(&path)
+}
+
+/// All recorded runs, oldest first.
+pub fn list_runs() -> Result<Vec<UpdateRun>> {
+    let dir = update_state_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut runs = Vec::new();
+    for entry in std::fs::read_dir(&dir).with_context(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        match read_run(&path) {
+            Ok(run) => runs.push(run),
+            Err(err) => eprintln!("Warning: skipping {}: {:#}", path.display(), err),
+        }
+    }
+    runs.sort_by(|a, b| a.started_at.cmp(&b.started_at).then(a.id.cmp(&b.id)));
+    Ok(runs)
+}
+
+fn read_run(path: &Path) -> Result<UpdateRun> {
+    let raw =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    serde_json::from_str(&raw).with_context(|| format!("parsing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InstallProgress, InstallStatus, StageLog, UpdateRun, UpdateStage};
+    use crate::commands::update::WaveOptions;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn stage_logs_are_numbered_and_timestamped() {
+        let dir = TempDir::new().expect("tempdir");
+        let logs = dir.path().join("forum");
+        let mut log = StageLog::open(&logs, "OS update", "apt upgrade -y");
+        log.lines("out", "Reading package lists...\nDone");
+        drop(log);
+        StageLog::open(&logs, "Discourse rebuild", "./launcher rebuild app");
+
+        let raw = std::fs::read_to_string(logs.join("01-os-update.log")).expect("log");
+        let lines: Vec<&str> = raw.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with(" cmd | apt upgrade -y"), "{}", lines[0]);
+        assert!(lines[2].ends_with("Z out | Done"), "{}", lines[2]);
+        assert!(logs.join("02-discourse-rebuild.log").exists());
+    }
+
+    #[test]
+    fn reopened_run_retries_failed_installs_from_their_stages() {
+        let mut done = InstallProgress::new("done");
+        done.status = InstallStatus::Ok;
+        let mut failed = InstallProgress::new("failed");
+        failed.status = InstallStatus::Failed;
+        failed.error = Some("reboot timed out".to_string());
+        failed.complete(UpdateStage::DiskChecked);
+        failed.complete(UpdateStage::OsUpdated);
+        failed.complete(UpdateStage::OsUpdated);
+        let run = UpdateRun {
+            id: "20261019-020000".to_string(),
+            started_at: Utc::now(),
+            finished_at: Some(Utc::now()),
+            post_changelog: true,
+            backup_first: false,
+            zero_downtime: false,
+            health_check: true,
+            waves: Some(WaveOptions {
+                batch_size: 3,
+                max_failure_rate: 25.0,
+            }),
+            installs: vec![done, failed],
+        };
+
+        let raw = serde_json::to_string(&run).expect("serialize");
+        assert!(raw.contains("\"os_updated\""));
+        let mut run: UpdateRun = serde_json::from_str(&raw).expect("deserialize");
+        assert!(run.health_check);
+        assert_eq!(run.waves.map(|waves| waves.batch_size), Some(3));
+        run.reopen();
+
+        assert!(run.finished_at.is_none());
+        assert_eq!(run.unfinished(), ["failed"]);
+        let failed = &run.installs[1];
+        assert_eq!(failed.status, InstallStatus::Pending);
+        assert!(failed.error.is_none());
+        assert_eq!(
+            failed.stages,
+            [UpdateStage::DiskChecked, UpdateStage::OsUpdated]
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::{Result, anyhow};
+use std::collections::BTreeMap;
+
+/// Values a changelog template can refer to. Empty strings count as unset.
+pub type TemplateVars = BTreeMap<&'static str, String>;
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Var {
+        name: String,
+        filters: Vec<String>,
+    },
+    If {
+        name: String,
+        negate: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+/// Render a changelog template.
+///
+/// Supports `{{ name }}` placeholders with `| truncate N`, `| default "text"`
+/// and `| upper`/`| lower` filters, and `{% if name %}`/`{% if not name %}`
+/// blocks with an optional `{% else %}`, closed by `{% endif %}`. Unknown
+/// names are an error so typos do not silently render as blanks.
+pub fn render_template(template: &str, vars: &TemplateVars) -> Result<String> {
+    let mut tokens = tokenize(template)?.into_iter();
+    let (nodes, end) = parse(&mut tokens)?;
+    if let Some(tag) = end {
+        return Err(anyhow!("unexpected {{% {} %}} in changelog template", tag));
+    }
+    let mut out = String::new();
+    render(&nodes, vars, &mut out)?;
+    Ok(out)
+}
+
+enum Token {
+    Text(String),
+    Var(String),
+    Tag(String),
+}
+
+fn tokenize(template: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+    while !rest.is_empty() {
+        let next = [("{{", "}}"), ("{%", "%}")]
+            .into_iter()
+            .filter_map(|(open, close)| rest.find(open).map(|at| (at, open, close)))
+            .min_by_key(|(at, _, _)| *at);
+        let Some((at, open, close)) = next else {
+            tokens.push(Token::Text(rest.to_string()));
+            break;
+        };
+        if at > 0 {
+            tokens.push(Token::Text(rest[..at].to_string()));
+        }
+        let inner_start = at + open.len();
+        let inner_len = rest[inner_start..]
+            .find(close)
+            .ok_or_else(|| anyhow!("unclosed {} in changelog template", open))?;
+        let inner = rest[inner_start..inner_start + inner_len]
+            .trim()
+            .to_string();
+        tokens.push(if open == "{{" {
+            Token::Var(inner)
+        } else {
+            Token::Tag(inner)
+        });
+        rest = &rest[inner_start + inner_len + close.len()..];
+    }
+    Ok(tokens)
+}
+
+/// Parse nodes up to the end of input or an `else`/`endif` tag, which is returned.
+fn parse(tokens: &mut impl Iterator<Item = Token>) -> Result<(Vec<Node>, Option<String>)> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Text(text) => nodes.push(Node::Text(text)),
+            Token::Var(inner) => {
+                let mut parts = inner.split('|').map(|part| part.trim().to_string());
+                let name = parts.next().unwrap_or_default();
+                nodes.push(Node::Var {
+                    name,
+                    filters: parts.collect(),
+                });
+            }
+            Token::Tag(tag) if tag == "else" || tag == "endif" => return Ok((nodes, Some(tag))),
+            Token::Tag(tag) => {
+                let condition = tag
+                    .strip_prefix("if ")
+                    .ok_or_else(|| anyhow!("unknown tag {{% {} %}} in changelog template", tag))?
+                    .trim();
+                let (negate, name) = match condition.strip_prefix("not ") {
+                    Some(name) => (true, name.trim()),
+                    None => (false, condition),
+                };
+                let (then, end) = parse(tokens)?;
+                let otherwise = match end.as_deref() {
+                    Some("else") => match parse(tokens)? {
+                        (otherwise, Some(end)) if end == "endif" => otherwise,
+                        _ => return Err(anyhow!("missing {{% endif %}} in changelog template")),
+                    },
+                    Some("endif") => Vec::new(),
+                    _ => return Err(anyhow!("missing {{% endif %}} in changelog template")),
+                };
+                nodes.push(Node::If {
+                    name: name.to_string(),
+                    negate,
+                    then,
+                    otherwise,
+                });
+            }
+        }
+    }
+    Ok((nodes, None))
+}
+
+fn lookup<'a>(vars: &'a TemplateVars, name: &str) -> Result<&'a str> {
+    vars.get(name)
+        .map(String::as_str)
+        .ok_or_else(|| anyhow!("unknown placeholder '{}' in changelog template", name))
+}
+
+fn render(nodes: &[Node], vars: &TemplateVars, out: &mut String) -> Result<()> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var { name, filters } => {
+                let mut value = lookup(vars, name)?.to_string();
+                for filter in filters {
+                    value = apply_filter(&value, filter)?;
+                }
+                out.push_str(&value);
+            }
+            Node::If {
+                name,
+                negate,
+                then,
+                otherwise,
+            } => {
+                let value = lookup(vars, name)?;
+                let set = !value.is_empty() && value != "false";
+                render(if set != *negate { then } else { otherwise }, vars, out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_filter(value: &str, filter: &str) -> Result<String> {
+    let (name, arg) = filter
+        .split_once(char::is_whitespace)
+        .map(|(name, arg)| (name, arg.trim()))
+        .unwrap_or((filter, ""));
+    match name {
+        "truncate" => {
+            let len: usize = arg
+                .parse()
+                .map_err(|_| anyhow!("truncate needs a length, got '{}'", arg))?;
+            Ok(value.chars().take(len).collect())
+        }
+        "default" if value.is_empty() => Ok(arg.trim_matches('"').to_string()),
+        "default" => Ok(value.to_string()),
+        "upper" => Ok(value.to_uppercase()),
+        "lower" => Ok(value.to_lowercase()),
+        _ => Err(anyhow!("unknown filter '{}' in changelog template", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TemplateVars, render_template};
+
+    fn vars() -> TemplateVars {
+        TemplateVars::from([
+            ("name", "forum".to_string()),
+            ("after_commit", "0123456789abcdef".to_string()),
+            ("backup_filename", String::new()),
+            ("rebooted", "true".to_string()),
+        ])
+    }
+
+    #[test]
+    fn placeholders_filters_and_conditions_render() {
+        let template = "{{ name | upper }} @ {{ after_commit | truncate 7 }}\n\
+            {% if backup_filename %}backup{% else %}no backup: {{ backup_filename | default \"none\" }}{% endif %}\n\
+            {% if not rebooted %}up{% else %}rebooted{% endif %}";
+        assert_eq!(
+            render_template(template, &vars()).expect("render"),
+            "FORUM @ 0123456\nno backup: none\nrebooted"
+        );
+    }
+
+    #[test]
+    fn template_errors_name_the_problem() {
+        let err = render_template("{{ nmae }}", &vars()).unwrap_err();
+        assert!(err.to_string().contains("unknown placeholder 'nmae'"));
+        assert!(render_template("{% if name %}open", &vars()).is_err());
+        assert!(render_template("{{ name | shout }}", &vars()).is_err());
+    }
+}
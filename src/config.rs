@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -25,6 +26,15 @@ where
 pub struct Config {
     #[serde(default)]
     pub discourse: Vec<DiscourseConfig>,
+    /// Named update recipes, referenced by `update_recipe` on a discourse.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub update_recipes: BTreeMap<String, UpdateRecipe>,
+    /// Changelog settings for every discourse (overridden per discourse).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changelog: Option<ChangelogConfig>,
+    /// Webhooks notified about updates of every discourse (overridden per discourse).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notify: Vec<NotifyConfig>,
 }
 
 /// Configuration for a single Discourse install.
@@ -44,6 +54,146 @@ pub struct DiscourseConfig {
     pub changelog_topic_id: Option<u64>,
     #[serde(default, deserialize_with = "deserialize_opt_string_empty_as_none")]
     pub ssh_host: Option<String>,
+    #[serde(default)]
+    pub backup_before_update: Option<bool>,
+    /// Name of an entry in `[update_recipes]` to use for `dsc update`.
+    #[serde(default, deserialize_with = "deserialize_opt_string_empty_as_none")]
+    pub update_recipe: Option<String>,
+    /// Inline update recipe; takes precedence over `update_recipe`.
+    #[serde(default)]
+    pub update: Option<UpdateRecipe>,
+    /// Changelog settings; fields set here win over the global `[changelog]`.
+    #[serde(default)]
+    pub changelog: Option<ChangelogConfig>,
+    /// Webhooks for this discourse; replaces the global `[[notify]]` list.
+    #[serde(default)]
+    pub notify: Option<Vec<NotifyConfig>>,
+}
+
+/// A webhook that receives update (and optionally backup) events.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotifyConfig {
+    pub url: String,
+    /// Payload shape (default: the raw event JSON).
+    #[serde(default)]
+    pub format: NotifyFormat,
+    /// Also send `dsc backup create` events.
+    #[serde(default)]
+    pub backups: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyFormat {
+    #[default]
+    Json,
+    Slack,
+    Mattermost,
+    Matrix,
+}
+
+/// How `dsc update` words and posts its changelog.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct ChangelogConfig {
+    /// Template file for the changelog body (default: the built-in checklist).
+    #[serde(default)]
+    pub template: Option<PathBuf>,
+    /// Post a new topic per update in this category instead of replying to
+    /// `changelog_topic_id`.
+    #[serde(default, deserialize_with = "deserialize_opt_u64_zero_as_none")]
+    pub category_id: Option<u64>,
+    /// Title template for new topics (default: `{{ name }} updated to {{ after_version }}`).
+    #[serde(default, deserialize_with = "deserialize_opt_string_empty_as_none")]
+    pub title: Option<String>,
+}
+
+/// How `dsc update` updates an install: thresholds plus the ordered remote steps.
+/// Unset values fall back to the `DSC_SSH_*`/`DSC_DISCOURSE_*` environment defaults.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct UpdateRecipe {
+    /// Abort when the root disk has less free space than this (GB).
+    #[serde(default)]
+    pub min_free_gb: Option<u64>,
+    /// Seconds to wait after the rebuild before checking the site.
+    #[serde(default)]
+    pub boot_wait_secs: Option<u64>,
+    /// Steps in run order (default: os_update, reboot, rebuild, cleanup).
+    #[serde(default)]
+    pub steps: Option<Vec<UpdateStep>>,
+    /// Checks run after the rebuild; unset means no smoke checks.
+    #[serde(default)]
+    pub smoke: Option<SmokeChecks>,
+}
+
+/// Post-rebuild smoke checks of an update recipe.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct SmokeChecks {
+    /// Paths that must load (default: `/`, `/latest.json`, `/srv/status`).
+    #[serde(default)]
+    pub paths: Option<Vec<String>>,
+    /// API path that must load with the configured apikey/api_username.
+    #[serde(default, deserialize_with = "deserialize_opt_string_empty_as_none")]
+    pub api_path: Option<String>,
+    /// Rebuild log substrings that fail the check (empty list disables the scan).
+    #[serde(default)]
+    pub log_patterns: Option<Vec<String>>,
+    /// Roll back when a check fails (default: true).
+    #[serde(default)]
+    pub rollback: Option<bool>,
+}
+
+/// One remote step of an update recipe.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateStep {
+    pub step: UpdateStepKind,
+    /// Label for `command` steps; also identifies the step when resuming.
+    #[serde(default, deserialize_with = "deserialize_opt_string_empty_as_none")]
+    pub name: Option<String>,
+    /// Remote command (default: the built-in command for the step kind).
+    #[serde(default, deserialize_with = "deserialize_opt_string_empty_as_none")]
+    pub command: Option<String>,
+    /// Abort the step after this many seconds. For `reboot`, how long to wait
+    /// for SSH to come back.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Never run this step.
+    #[serde(default)]
+    pub skip: bool,
+    /// Remote command; the step is skipped when it exits successfully.
+    #[serde(default, deserialize_with = "deserialize_opt_string_empty_as_none")]
+    pub skip_if: Option<String>,
+    /// Remote command run when the step fails.
+    #[serde(default, deserialize_with = "deserialize_opt_string_empty_as_none")]
+    pub rollback: Option<String>,
+    /// For `rebuild` without a `command`: bootstrap web-only containers
+    /// before destroying/starting them, instead of `launcher rebuild`.
+    #[serde(default)]
+    pub zero_downtime: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStepKind {
+    OsUpdate,
+    Reboot,
+    Rebuild,
+    Cleanup,
+    Command,
+}
+
+impl UpdateStep {
+    pub fn new(step: UpdateStepKind) -> Self {
+        UpdateStep {
+            step,
+            name: None,
+            command: None,
+            timeout_secs: None,
+            skip: false,
+            skip_if: None,
+            rollback: None,
+            zero_downtime: false,
+        }
+    }
 }
 
 /// Load configuration from a TOML file.
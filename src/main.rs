@@ -47,21 +47,135 @@ fn main() -> Result<()> {
             save_config(&config_path, &config)
         }
 
+        Commands::Update {
+            command: Some(UpdateCommand::Status { run }),
+            ..
+        } => commands::update::update_status(run.as_deref()),
+
+        Commands::Update {
+            command: Some(UpdateCommand::Logs { name, run }),
+            ..
+        } => commands::update::update_logs(&name, run.as_deref()),
+
+        Commands::Update {
+            check: true,
+            name,
+            parallel,
+            max,
+            report,
+            tags,
+            exclude,
+            ..
+        } => {
+            if max.is_some() && !parallel {
+                return Err(anyhow!("--max requires --parallel"));
+            }
+            let targets = match name.as_deref().unwrap_or("all") {
+                "all" => commands::update::select_update_targets(
+                    &config,
+                    tags.as_deref(),
+                    exclude.as_deref(),
+                    None,
+                )?,
+                _ if tags.is_some() || exclude.is_some() => {
+                    return Err(anyhow!("--tags/--exclude only apply to 'dsc update all'"));
+                }
+                name => vec![commands::update::update_target(&config, name)?],
+            };
+            commands::update_check::update_check(&targets, parallel, max, report.as_deref())
+        }
+
+        Commands::Update {
+            resume: true,
+            run,
+            parallel,
+            max,
+            yes,
+            report,
+            ..
+        } => {
+            if max.is_some() && !parallel {
+                return Err(anyhow!("--max requires --parallel"));
+            }
+            let status = commands::update::update_resume(
+                &config,
+                run.as_deref(),
+                parallel,
+                max,
+                yes,
+                report.as_deref(),
+            )?;
+            if status.exit_code() != 0 {
+                std::process::exit(status.exit_code());
+            }
+            Ok(())
+        }
+
         Commands::Update {
             name,
             parallel,
             max,
             post_changelog,
             yes,
-        } => match name.as_str() {
-            "all" if max.is_some() && !parallel => Err(anyhow!("--max requires --parallel")),
-            "all" if max == Some(0) => Err(anyhow!("--max must be at least 1")),
-            "all" => commands::update::update_all(&config, parallel, max, post_changelog, yes),
-            _ if parallel || max.is_some() => {
-                Err(anyhow!("--parallel/--max only apply to 'dsc update all'"))
+            backup_first,
+            zero_downtime,
+            report,
+            tags,
+            exclude,
+            waves,
+            canary,
+            batch_size,
+            max_failure_rate,
+            ..
+        } => {
+            let options = commands::update::UpdateOptions {
+                post_changelog,
+                yes,
+                backup_first,
+                zero_downtime,
+                health_check: false,
+            };
+            let fleet = tags.is_some() || exclude.is_some();
+            match name.as_deref().unwrap_or("all") {
+                "all" if max.is_some() && !parallel => Err(anyhow!("--max requires --parallel")),
+                "all" if max == Some(0) => Err(anyhow!("--max must be at least 1")),
+                "all" if waves && batch_size == 0 => {
+                    Err(anyhow!("--batch-size must be at least 1"))
+                }
+                "all" => {
+                    let targets = commands::update::select_update_targets(
+                        &config,
+                        tags.as_deref(),
+                        exclude.as_deref(),
+                        canary.as_deref(),
+                    )?;
+                    let waves = waves.then_some(commands::update::WaveOptions {
+                        batch_size,
+                        max_failure_rate,
+                    });
+                    let status = commands::update::update_all(
+                        &targets,
+                        parallel,
+                        max,
+                        options,
+                        waves,
+                        report.as_deref(),
+                    )?;
+                    if status.exit_code() != 0 {
+                        std::process::exit(status.exit_code());
+                    }
+                    Ok(())
+                }
+                _ if fleet || waves => Err(anyhow!(
+                    "--tags/--exclude/--waves only apply to 'dsc update all'"
+                )),
+                _ if parallel || max.is_some() => {
+                    Err(anyhow!("--parallel/--max only apply to 'dsc update all'"))
+                }
+                _ if report.is_some() => Err(anyhow!("--report only applies to 'dsc update all'")),
+                name => commands::update::update_one(&config, name, options),
             }
-            _ => commands::update::update_one(&config, &name, post_changelog, yes),
-        },
+        }
 
         Commands::Emoji {
             command:
@@ -135,6 +249,89 @@ fn main() -> Result<()> {
                 local_path,
                 category,
             } => commands::category::category_push(&config, &discourse, &category, &local_path),
+
+            CategoryCommand::Create {
+                discourse,
+                name,
+                slug,
+                color,
+                text_color,
+                parent,
+            } => commands::category::category_create(
+                &config,
+                &discourse,
+                &name,
+                slug.as_deref(),
+                color.as_deref(),
+                text_color.as_deref(),
+                parent.as_deref(),
+            ),
+
+            CategoryCommand::Update {
+                discourse,
+                category,
+                name,
+                slug,
+                color,
+                text_color,
+            } => commands::category::category_update(
+                &config,
+                &discourse,
+                &category,
+                name.as_deref(),
+                slug.as_deref(),
+                color.as_deref(),
+                text_color.as_deref(),
+            ),
+
+            CategoryCommand::Move {
+                discourse,
+                category,
+                parent,
+                top_level,
+                position,
+            } => commands::category::category_move(
+                &config,
+                &discourse,
+                &category,
+                parent.as_deref(),
+                top_level,
+                position,
+            ),
+
+            CategoryCommand::Reorder {
+                discourse,
+                outline,
+                dry_run,
+            } => commands::category::category_reorder(&config, &discourse, &outline, dry_run),
+
+            CategoryCommand::Delete {
+                discourse,
+                category,
+                yes,
+            } => commands::category::category_delete(&config, &discourse, &category, yes),
+
+            CategoryCommand::Permissions {
+                command:
+                    Some(CategoryPermissionsCommand::Set {
+                        discourse,
+                        category,
+                        group,
+                        level,
+                    }),
+                ..
+            } => commands::category::category_permissions_set(
+                &config, &discourse, &category, &group, level,
+            ),
+
+            CategoryCommand::Permissions {
+                discourse, format, ..
+            } => match discourse {
+                Some(discourse) => {
+                    commands::category::category_permissions(&config, &discourse, format)
+                }
+                None => Err(anyhow!("missing discourse for command")),
+            },
         },
 
         Commands::Group { command } => match command {
@@ -162,9 +359,11 @@ fn main() -> Result<()> {
         },
 
         Commands::Backup { command } => match command {
-            BackupCommand::Create { discourse } => {
-                commands::backup::backup_create(&config, &discourse)
-            }
+            BackupCommand::Create {
+                discourse,
+                wait,
+                timeout,
+            } => commands::backup::backup_create(&config, &discourse, wait, timeout),
 
             BackupCommand::List {
                 discourse,
@@ -175,7 +374,64 @@ fn main() -> Result<()> {
             BackupCommand::Restore {
                 discourse,
                 backup_path,
-            } => commands::backup::backup_restore(&config, &discourse, &backup_path),
+                from_local,
+                wait,
+                timeout,
+            } => commands::backup::backup_restore(
+                &config,
+                &discourse,
+                backup_path.as_deref(),
+                from_local.as_deref(),
+                wait,
+                timeout,
+            ),
+
+            BackupCommand::Prune {
+                discourse,
+                local,
+                keep_last,
+                keep_daily,
+                keep_monthly,
+                dry_run,
+            } => commands::backup::backup_prune(
+                &config,
+                discourse.as_deref(),
+                local.as_deref(),
+                commands::backup::RetentionPolicy {
+                    keep_last,
+                    keep_daily,
+                    keep_monthly,
+                },
+                dry_run,
+            ),
+
+            BackupCommand::Status { discourse, cancel } => {
+                commands::backup::backup_status(&config, &discourse, cancel)
+            }
+
+            BackupCommand::Upload {
+                discourse,
+                local_path,
+            } => commands::backup::backup_upload(&config, &discourse, &local_path),
+
+            BackupCommand::Download {
+                discourse,
+                filename,
+                dir,
+                token,
+                ssh,
+            } => commands::backup::backup_download(
+                &config,
+                &discourse,
+                &filename,
+                dir.as_deref(),
+                token.as_deref(),
+                ssh,
+            ),
+
+            BackupCommand::PullLatest { dir, tags, ssh } => {
+                commands::backup::backup_pull_latest(&config, tags.as_deref(), dir.as_deref(), ssh)
+            }
         },
 
         Commands::Palette { command } => match command {
@@ -201,6 +457,44 @@ fn main() -> Result<()> {
                 local_path,
                 palette_id,
             } => commands::palette::palette_push(&config, &discourse, &local_path, palette_id),
+
+            PaletteCommand::Generate {
+                primary,
+                secondary,
+                tertiary,
+                name,
+                dark,
+                output,
+            } => commands::palette::palette_generate(
+                &primary,
+                &secondary,
+                &tertiary,
+                &name,
+                dark,
+                output.as_deref(),
+            ),
+
+            PaletteCommand::Check { local_path } => commands::palette::palette_check(&local_path),
+
+            PaletteCommand::Diff { left, right } => {
+                commands::palette::palette_diff(&config, &left, &right)
+            }
+
+            PaletteCommand::Copy {
+                discourse,
+                target,
+                palette_id,
+            } => {
+                commands::palette::palette_copy(&config, &discourse, target.as_deref(), palette_id)
+            }
+
+            PaletteCommand::Render { local_path, output } => {
+                commands::palette::palette_render(&local_path, output.as_deref())
+            }
+
+            PaletteCommand::Sync { local_path, tags } => {
+                commands::palette::palette_sync(&config, &local_path, tags.as_deref())
+            }
         },
 
         Commands::Plugin { command } => match command {
@@ -223,31 +517,135 @@ fn main() -> Result<()> {
                 format,
                 verbose,
             } => commands::theme::theme_list(&config, &discourse, format, verbose),
-            ThemeCommand::Install { discourse, url } => {
-                commands::theme::theme_install(&config, &discourse, &url)
-            }
-            ThemeCommand::Remove { discourse, name } => {
-                commands::theme::theme_remove(&config, &discourse, &name)
-            }
+            ThemeCommand::Install {
+                discourse,
+                url,
+                branch,
+                private_key,
+                ssh,
+            } => commands::theme::theme_install(
+                &config,
+                &discourse,
+                &url,
+                branch.as_deref(),
+                private_key.as_deref(),
+                ssh,
+            ),
+            ThemeCommand::Remove {
+                discourse,
+                name,
+                ssh,
+            } => commands::theme::theme_remove(&config, &discourse, &name, ssh),
             ThemeCommand::Pull {
                 discourse,
                 theme_id,
                 local_path,
+                dir,
             } => commands::theme::theme_pull(
                 &config,
                 &discourse,
                 theme_id,
                 local_path.as_deref(),
+                dir,
             ),
             ThemeCommand::Push {
                 discourse,
                 local_path,
                 theme_id,
-            } => commands::theme::theme_push(&config, &discourse, &local_path, theme_id),
+                dir,
+            } => commands::theme::theme_push(&config, &discourse, &local_path, theme_id, dir),
             ThemeCommand::Duplicate {
                 discourse,
                 theme_id,
             } => commands::theme::theme_duplicate(&config, &discourse, theme_id),
+            ThemeCommand::Export {
+                discourse,
+                theme_id,
+                local_path,
+            } => {
+                commands::theme::theme_export(&config, &discourse, theme_id, local_path.as_deref())
+            }
+            ThemeCommand::Import {
+                discourse,
+                local_path,
+            } => commands::theme::theme_import(&config, &discourse, &local_path),
+            ThemeCommand::Copy {
+                discourse,
+                target,
+                theme_id,
+                target_theme,
+            } => commands::theme::theme_copy(
+                &config,
+                &discourse,
+                target.as_deref(),
+                theme_id,
+                target_theme,
+            ),
+            ThemeCommand::Watch {
+                discourse,
+                theme_id,
+                dir,
+                debounce,
+                open,
+            } => commands::theme::theme_watch(&config, &discourse, theme_id, &dir, debounce, open),
+            ThemeCommand::Settings { command } => match command {
+                ThemeSettingsCommand::List {
+                    discourse,
+                    theme_id,
+                    format,
+                    verbose,
+                } => commands::theme::theme_settings_list(
+                    &config, &discourse, theme_id, format, verbose,
+                ),
+                ThemeSettingsCommand::Get {
+                    discourse,
+                    theme_id,
+                    setting,
+                } => commands::theme::theme_settings_get(&config, &discourse, theme_id, &setting),
+                ThemeSettingsCommand::Set {
+                    discourse,
+                    theme_id,
+                    setting,
+                    value,
+                } => commands::theme::theme_settings_set(
+                    &config, &discourse, theme_id, &setting, &value,
+                ),
+            },
+            ThemeCommand::Component { command } => match command {
+                ThemeComponentCommand::Add {
+                    discourse,
+                    parent,
+                    child,
+                } => commands::theme::theme_component_add(&config, &discourse, parent, child),
+                ThemeComponentCommand::Remove {
+                    discourse,
+                    parent,
+                    child,
+                } => commands::theme::theme_component_remove(&config, &discourse, parent, child),
+            },
+            ThemeCommand::SetDefault {
+                discourse,
+                theme_id,
+            } => commands::theme::theme_set_default(&config, &discourse, theme_id),
+            ThemeCommand::Enable {
+                discourse,
+                theme_id,
+            } => commands::theme::theme_set_enabled(&config, &discourse, theme_id, true),
+            ThemeCommand::Disable {
+                discourse,
+                theme_id,
+            } => commands::theme::theme_set_enabled(&config, &discourse, theme_id, false),
+            ThemeCommand::UserSelectable {
+                discourse,
+                theme_id,
+                selectable,
+            } => commands::theme::theme_set_user_selectable(
+                &config, &discourse, theme_id, selectable,
+            ),
+            ThemeCommand::UpdateRemote {
+                discourse,
+                theme_id,
+            } => commands::theme::theme_update_remote(&config, &discourse, theme_id),
         },
 
         Commands::Setting {
@@ -70,3 +70,119 @@ fn backup_restore() {
     );
     assert!(output.status.success(), "backup restore failed");
 }
+
+#[test]
+fn backup_download_ssh() {
+    let Some(test) = test_discourse() else {
+        return;
+    };
+    if test.backup_enabled != Some(true) || test.ssh_enabled != Some(true) {
+        return;
+    }
+    let Some(backup_path) = test.test_backup_path.as_ref() else {
+        return;
+    };
+    vprintln("e2e_backup_download_ssh: downloading backup over SSH");
+    let ssh_host_line = test
+        .ssh_host
+        .as_ref()
+        .map(|host| format!("ssh_host = \"{}\"\n", host))
+        .unwrap_or_default();
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[discourse]]\nname = \"{}\"\nbaseurl = \"{}\"\napikey = \"{}\"\napi_username = \"{}\"\n{}",
+            test.name, test.baseurl, test.apikey, test.api_username, ssh_host_line
+        ),
+    );
+    let download_dir = dir.path().join("backups");
+    let output = run_dsc(
+        &[
+            "backup",
+            "download",
+            &test.name,
+            backup_path,
+            download_dir.to_str().unwrap(),
+            "--ssh",
+        ],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "backup download failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let path = download_dir.join(backup_path);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        path.to_str().unwrap()
+    );
+    let size = std::fs::metadata(&path).expect("downloaded backup").len();
+    assert!(size > 0, "downloaded backup is empty");
+}
+
+#[test]
+fn backup_upload() {
+    let Some(test) = test_discourse() else {
+        return;
+    };
+    if test.backup_enabled != Some(true) {
+        return;
+    }
+    let Some(upload_path) = test.test_backup_upload_path.as_ref() else {
+        return;
+    };
+    vprintln("e2e_backup_upload: uploading a local backup archive");
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[discourse]]\nname = \"{}\"\nbaseurl = \"{}\"\napikey = \"{}\"\napi_username = \"{}\"\n",
+            test.name, test.baseurl, test.apikey, test.api_username
+        ),
+    );
+    let output = run_dsc(&["backup", "upload", &test.name, upload_path], &config_path);
+    assert!(
+        output.status.success(),
+        "backup upload failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let filename = std::path::Path::new(upload_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), filename);
+}
+
+#[test]
+fn backup_status() {
+    let Some(test) = test_discourse() else {
+        return;
+    };
+    if test.backup_enabled != Some(true) {
+        return;
+    }
+    vprintln("e2e_backup_status: showing backup operation status");
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[discourse]]\nname = \"{}\"\nbaseurl = \"{}\"\napikey = \"{}\"\napi_username = \"{}\"\n",
+            test.name, test.baseurl, test.apikey, test.api_username
+        ),
+    );
+    let output = run_dsc(&["backup", "status", &test.name], &config_path);
+    assert!(
+        output.status.success(),
+        "backup status failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let state = stdout.lines().next().unwrap_or_default();
+    assert!(
+        state == "running" || state == "idle",
+        "unexpected backup status: {}",
+        stdout
+    );
+}
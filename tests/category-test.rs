@@ -153,3 +153,187 @@ fn category_push() {
         .any(|topic| topic.title.contains(&marker));
     assert!(found, "new category topic not found");
 }
+
+#[test]
+fn category_create_update_move_delete() {
+    let Some(test) = test_discourse() else {
+        return;
+    };
+    let Some(parent_id) = test.test_category_id else {
+        return;
+    };
+    let marker = Uuid::new_v4().to_string();
+    vprintln("e2e_category_manage: create, update, move and delete a category");
+
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[discourse]]\nname = \"{}\"\nbaseurl = \"{}\"\napikey = \"{}\"\napi_username = \"{}\"\n",
+            test.name, test.baseurl, test.apikey, test.api_username
+        ),
+    );
+    let name = format!("E2E {}", &marker[..8]);
+    let output = run_dsc(&["category", "create", &test.name, &name], &config_path);
+    assert!(
+        output.status.success(),
+        "category create failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let category_id = url
+        .rsplit('/')
+        .next()
+        .and_then(|id| id.parse::<u64>().ok())
+        .expect("category create should print the category URL");
+
+    let renamed = format!("{} renamed", name);
+    let output = run_dsc(
+        &[
+            "category",
+            "update",
+            &test.name,
+            &category_id.to_string(),
+            "--name",
+            &renamed,
+        ],
+        &config_path,
+    );
+    assert!(output.status.success(), "category update failed");
+
+    let output = run_dsc(
+        &[
+            "category",
+            "move",
+            &test.name,
+            &category_id.to_string(),
+            "--parent",
+            &parent_id.to_string(),
+        ],
+        &config_path,
+    );
+    assert!(output.status.success(), "category move failed");
+
+    let client = DiscourseClient::new(&to_config(&test)).expect("client");
+    let categories = client.fetch_categories().expect("categories");
+    let moved = categories
+        .iter()
+        .flat_map(|cat| std::iter::once(cat).chain(cat.subcategory_list.iter()))
+        .find(|cat| cat.id == Some(category_id))
+        .expect("moved category");
+    assert_eq!(moved.name, renamed, "category name not updated");
+    assert_eq!(
+        moved.parent_category_id,
+        Some(parent_id),
+        "category not moved"
+    );
+
+    let output = run_dsc(
+        &[
+            "category",
+            "delete",
+            &test.name,
+            &category_id.to_string(),
+            "-y",
+        ],
+        &config_path,
+    );
+    assert!(output.status.success(), "category delete failed");
+}
+
+#[test]
+fn category_permissions() {
+    let Some(test) = test_discourse() else {
+        return;
+    };
+    vprintln("e2e_category_permissions: print the permission matrix");
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[discourse]]\nname = \"{}\"\nbaseurl = \"{}\"\napikey = \"{}\"\napi_username = \"{}\"\n",
+            test.name, test.baseurl, test.apikey, test.api_username
+        ),
+    );
+    let output = run_dsc(
+        &["category", "permissions", &test.name, "--format", "csv"],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "category permissions failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("category_id,category"),
+        "unexpected csv header: {}",
+        stdout
+    );
+}
+
+#[test]
+fn category_permissions_set() {
+    let Some(test) = test_discourse() else {
+        return;
+    };
+    let Some(category_id) = test.test_category_id else {
+        return;
+    };
+    vprintln("e2e_category_permissions_set: grant then revoke staff on the test category");
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[discourse]]\nname = \"{}\"\nbaseurl = \"{}\"\napikey = \"{}\"\napi_username = \"{}\"\n",
+            test.name, test.baseurl, test.apikey, test.api_username
+        ),
+    );
+    let client = DiscourseClient::new(&to_config(&test)).expect("client");
+    let had_staff = client
+        .fetch_category_permissions(category_id)
+        .expect("permissions")
+        .iter()
+        .any(|permission| permission.group_name == "staff");
+    if had_staff {
+        return;
+    }
+
+    let category = category_id.to_string();
+    let output = run_dsc(
+        &[
+            "category",
+            "permissions",
+            "set",
+            &test.name,
+            &category,
+            "staff",
+            "full",
+        ],
+        &config_path,
+    );
+    assert!(output.status.success(), "category permissions grant failed");
+    let granted = client
+        .fetch_category_permissions(category_id)
+        .expect("permissions")
+        .iter()
+        .any(|permission| permission.group_name == "staff" && permission.permission_type == 1);
+    assert!(granted, "staff permission not granted");
+
+    let output = run_dsc(
+        &[
+            "category",
+            "permissions",
+            "set",
+            &test.name,
+            &category,
+            "staff",
+            "none",
+        ],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "category permissions revoke failed"
+    );
+}
@@ -50,6 +50,7 @@ pub struct TestDiscourse {
     pub test_theme_id: Option<u64>,
     pub backup_enabled: Option<bool>,
     pub test_backup_path: Option<String>,
+    pub test_backup_upload_path: Option<String>,
 }
 
 fn load_test_config() -> Option<TestConfig> {
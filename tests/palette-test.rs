@@ -81,3 +81,127 @@ fn palette_pull_push() {
         .unwrap_or_default();
     assert_eq!(id, palette_id, "palette id mismatch");
 }
+
+#[test]
+fn palette_diff() {
+    let Some(test) = test_discourse() else {
+        return;
+    };
+    let Some(palette_id) = test.test_color_scheme_id else {
+        return;
+    };
+    vprintln("e2e_palette_diff: diff a pulled palette against the remote");
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[discourse]]\nname = \"{}\"\nbaseurl = \"{}\"\napikey = \"{}\"\napi_username = \"{}\"\n",
+            test.name, test.baseurl, test.apikey, test.api_username
+        ),
+    );
+    let palette_path = dir.path().join("palette.json");
+    let output = run_dsc(
+        &[
+            "palette",
+            "pull",
+            &test.name,
+            &palette_id.to_string(),
+            palette_path.to_str().unwrap(),
+        ],
+        &config_path,
+    );
+    assert!(output.status.success(), "palette pull failed");
+
+    let remote = format!("{}:{}", test.name, palette_id);
+    let output = run_dsc(
+        &["palette", "diff", palette_path.to_str().unwrap(), &remote],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "palette diff failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "No differences."
+    );
+}
+
+#[test]
+fn palette_generate_check_render() {
+    vprintln("e2e_palette_generate_check_render: generate, check and render a palette");
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(&dir, "");
+    let palette_path = dir.path().join("brand.yml");
+
+    let output = run_dsc(
+        &[
+            "palette",
+            "generate",
+            "--primary",
+            "222222",
+            "--secondary",
+            "ffffff",
+            "--tertiary",
+            "66ccff",
+            "--output",
+            palette_path.to_str().unwrap(),
+        ],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "palette generate failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(palette_path.exists(), "generated palette file not created");
+
+    let output = run_dsc(
+        &["palette", "check", palette_path.to_str().unwrap()],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "palette check failed: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let svg_path = dir.path().join("brand.svg");
+    let output = run_dsc(
+        &[
+            "palette",
+            "render",
+            palette_path.to_str().unwrap(),
+            "--output",
+            svg_path.to_str().unwrap(),
+        ],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "palette render failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let svg = fs::read_to_string(&svg_path).expect("read rendered svg");
+    assert!(svg.starts_with("<svg"), "render output is not SVG");
+    assert!(
+        svg.contains("header_background"),
+        "render missing swatch labels"
+    );
+
+    let bad_path = dir.path().join("bad.json");
+    fs::write(
+        &bad_path,
+        r#"{"name":"bad","colors":{"primary":"777777","secondary":"ffffff"}}"#,
+    )
+    .expect("write bad palette");
+    let output = run_dsc(
+        &["palette", "check", bad_path.to_str().unwrap()],
+        &config_path,
+    );
+    assert!(
+        !output.status.success(),
+        "palette check should fail below WCAG AA"
+    );
+}
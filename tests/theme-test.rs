@@ -24,6 +24,52 @@ fn theme_list() {
 
 #[test]
 fn theme_install_remove() {
+    let Some(test) = test_discourse() else {
+        return;
+    };
+    let Some(url) = test.test_theme_url.as_ref() else {
+        return;
+    };
+    vprintln("e2e_theme_install_remove: import theme via the admin API then remove it");
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[discourse]]\nname = \"{}\"\nbaseurl = \"{}\"\napikey = \"{}\"\napi_username = \"{}\"\n",
+            test.name, test.baseurl, test.apikey, test.api_username
+        ),
+    );
+
+    let output = run_dsc(&["theme", "install", &test.name, url], &config_path);
+    assert!(
+        output.status.success(),
+        "theme install failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let theme_id = stdout
+        .split(" - ")
+        .next()
+        .and_then(|id| id.trim().parse::<u64>().ok())
+        .expect("theme install should print '<id> - <name> - <status>'");
+
+    let output = run_dsc(
+        &["theme", "remove", &test.name, &theme_id.to_string()],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "theme remove failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        theme_id.to_string()
+    );
+}
+
+#[test]
+fn theme_install_remove_ssh() {
     let Some(test) = test_discourse() else {
         return;
     };
@@ -36,7 +82,7 @@ fn theme_install_remove() {
     let Some(name) = test.test_theme_name.as_ref() else {
         return;
     };
-    vprintln("e2e_theme_install_remove: install/remove theme");
+    vprintln("e2e_theme_install_remove_ssh: install/remove theme over SSH");
     let ssh_host_line = test
         .ssh_host
         .as_ref()
@@ -58,6 +104,7 @@ fn theme_install_remove() {
         .arg("install")
         .arg(&test.name)
         .arg(url)
+        .arg("--ssh")
         .env("DSC_SSH_THEME_INSTALL_CMD", "echo theme install {url}")
         .output()
         .expect("run theme install");
@@ -70,6 +117,7 @@ fn theme_install_remove() {
         .arg("remove")
         .arg(&test.name)
         .arg(name)
+        .arg("--ssh")
         .env("DSC_SSH_THEME_REMOVE_CMD", "echo theme remove {name}")
         .output()
         .expect("run theme remove");
@@ -143,6 +191,168 @@ fn theme_pull_push() {
     assert_eq!(returned_id, theme_id, "push should return the updated theme ID");
 }
 
+#[test]
+fn theme_pull_push_dir() {
+    let Some(test) = test_discourse() else {
+        return;
+    };
+    let Some(theme_id) = test.test_theme_id else {
+        return;
+    };
+    vprintln("e2e_theme_pull_push_dir: pull theme as a directory then push back");
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[discourse]]\nname = \"{}\"\nbaseurl = \"{}\"\napikey = \"{}\"\napi_username = \"{}\"\n",
+            test.name, test.baseurl, test.apikey, test.api_username
+        ),
+    );
+
+    let theme_dir = dir.path().join("pulled-theme");
+    let output = run_dsc(
+        &[
+            "theme",
+            "pull",
+            &test.name,
+            &theme_id.to_string(),
+            theme_dir.to_str().unwrap(),
+            "--dir",
+        ],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "theme pull --dir failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let about = std::fs::read_to_string(theme_dir.join("about.json")).expect("read about.json");
+    let about: serde_json::Value = serde_json::from_str(&about).expect("parse about.json");
+    assert!(about.get("name").is_some(), "about.json missing 'name'");
+
+    let output = run_dsc(
+        &[
+            "theme",
+            "push",
+            &test.name,
+            theme_dir.to_str().unwrap(),
+            &theme_id.to_string(),
+            "--dir",
+        ],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "theme push --dir failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let returned_id: u64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .expect("theme push should print numeric ID");
+    assert_eq!(returned_id, theme_id);
+}
+
+#[test]
+fn theme_settings_list() {
+    let Some(test) = test_discourse() else {
+        return;
+    };
+    let Some(theme_id) = test.test_theme_id else {
+        return;
+    };
+    vprintln("e2e_theme_settings_list: list theme settings");
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[discourse]]\nname = \"{}\"\nbaseurl = \"{}\"\napikey = \"{}\"\napi_username = \"{}\"\n",
+            test.name, test.baseurl, test.apikey, test.api_username
+        ),
+    );
+
+    let output = run_dsc(
+        &[
+            "theme",
+            "settings",
+            "list",
+            &test.name,
+            &theme_id.to_string(),
+            "--format",
+            "json",
+        ],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "theme settings list failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let parsed: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("parse theme settings json");
+    assert!(parsed.is_array(), "theme settings should be a JSON array");
+}
+
+#[test]
+fn theme_export_import() {
+    let Some(test) = test_discourse() else {
+        return;
+    };
+    let Some(theme_id) = test.test_theme_id else {
+        return;
+    };
+    vprintln("e2e_theme_export_import: export a bundle then import it");
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[discourse]]\nname = \"{}\"\nbaseurl = \"{}\"\napikey = \"{}\"\napi_username = \"{}\"\n",
+            test.name, test.baseurl, test.apikey, test.api_username
+        ),
+    );
+
+    let bundle_path = dir.path().join("theme.zip");
+    let output = run_dsc(
+        &[
+            "theme",
+            "export",
+            &test.name,
+            &theme_id.to_string(),
+            bundle_path.to_str().unwrap(),
+        ],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "theme export failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let size = std::fs::metadata(&bundle_path).expect("bundle").len();
+    assert!(size > 0, "exported bundle is empty");
+
+    let output = run_dsc(
+        &["theme", "import", &test.name, bundle_path.to_str().unwrap()],
+        &config_path,
+    );
+    assert!(
+        output.status.success(),
+        "theme import failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let new_id: u64 = stdout
+        .split(" - ")
+        .next()
+        .and_then(|id| id.trim().parse().ok())
+        .expect("theme import should print '<id> - <name> - <status>'");
+    assert_ne!(new_id, theme_id, "import should create a new theme");
+
+    let client = DiscourseClient::new(&to_config(&test)).expect("client");
+    client
+        .delete_theme(new_id)
+        .expect("failed to delete imported theme during cleanup");
+}
+
 #[test]
 fn theme_duplicate() {
     let Some(test) = test_discourse() else {
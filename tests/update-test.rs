@@ -1,7 +1,11 @@
 mod common;
 use common::*;
 use dsc::api::DiscourseClient;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
 use std::process::Command;
+use std::sync::mpsc;
+use std::thread;
 use std::time::Duration;
 use tempfile::TempDir;
 use uuid::Uuid;
@@ -44,6 +48,7 @@ fn update() {
         .env("DSC_SSH_OS_UPDATE_CMD", "echo OS packages updated")
         .env("DSC_SSH_REBOOT_CMD", "echo Server rebooted")
         .env("DSC_SSH_OS_VERSION_CMD", "echo Ubuntu 22.04.3 LTS")
+        .env("DSC_UPDATE_STATE_DIR", dir.path().join("state"))
         .output()
         .expect("run update");
     if !output.status.success() {
@@ -101,6 +106,7 @@ fn update_all() {
         .env("DSC_SSH_OS_UPDATE_CMD", "echo OS packages updated")
         .env("DSC_SSH_REBOOT_CMD", "echo Server rebooted")
         .env("DSC_SSH_OS_VERSION_CMD", "echo Ubuntu 22.04.3 LTS")
+        .env("DSC_UPDATE_STATE_DIR", dir.path().join("state"))
         .output()
         .expect("run update all");
     if !output.status.success() {
@@ -186,3 +192,202 @@ fn update_all_max_zero_is_rejected() {
         "unexpected stderr: {stderr}"
     );
 }
+
+#[test]
+fn update_all_wave_options_require_waves() {
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(&dir, "");
+    for flag in [
+        ["--canary", "forum"],
+        ["--batch-size", "3"],
+        ["--max-failure-rate", "10"],
+    ] {
+        let output = run_dsc(&["update", "all", flag[0], flag[1]], &config_path);
+        assert!(
+            !output.status.success(),
+            "update all {} without --waves should fail",
+            flag[0]
+        );
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--waves"), "unexpected stderr: {stderr}");
+    }
+}
+
+#[test]
+fn update_status_and_resume_without_runs() {
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(&dir, "");
+    let state_dir = dir.path().join("state");
+    let output = Command::new(env!("CARGO_BIN_EXE_dsc"))
+        .arg("-c")
+        .arg(&config_path)
+        .args(["update", "status"])
+        .env("DSC_UPDATE_STATE_DIR", &state_dir)
+        .output()
+        .expect("run update status");
+    assert!(output.status.success(), "update status failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No update runs recorded"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dsc"))
+        .arg("-c")
+        .arg(&config_path)
+        .args(["update", "--resume"])
+        .env("DSC_UPDATE_STATE_DIR", &state_dir)
+        .output()
+        .expect("run update --resume");
+    assert!(
+        !output.status.success(),
+        "update --resume should fail without runs"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no update run to resume"),
+        "unexpected stderr: {stderr}"
+    );
+}
+
+#[test]
+fn update_resume_rejects_run_options() {
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(&dir, "");
+    for args in [
+        &["forum"][..],
+        &["--tags", "prod"],
+        &["--exclude", "forum"],
+        &["--no-changelog"],
+        &["--backup-first"],
+        &["--zero-downtime"],
+        &["--waves"],
+    ] {
+        let mut argv = vec!["update", "--resume"];
+        argv.extend_from_slice(args);
+        let output = run_dsc(&argv, &config_path);
+        assert!(
+            !output.status.success(),
+            "update --resume {} should fail",
+            args[0]
+        );
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("cannot be used with"),
+            "unexpected stderr: {stderr}"
+        );
+    }
+}
+
+#[test]
+fn update_check_reports_without_installs() {
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(&dir, "");
+    let output = Command::new(env!("CARGO_BIN_EXE_dsc"))
+        .arg("-c")
+        .arg(&config_path)
+        .args(["update", "all", "--check"])
+        .env("DSC_GITHUB_API_URL", "http://127.0.0.1:9")
+        .output()
+        .expect("run update --check");
+    assert!(output.status.success(), "update --check failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Update check (upstream: "), "{stdout}");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("could not read the latest"));
+}
+
+#[test]
+fn update_logs_without_runs_fails() {
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(&dir, "");
+    let output = Command::new(env!("CARGO_BIN_EXE_dsc"))
+        .arg("-c")
+        .arg(&config_path)
+        .args(["update", "logs", "forum"])
+        .env("DSC_UPDATE_STATE_DIR", dir.path().join("state"))
+        .output()
+        .expect("run update logs");
+    assert!(
+        !output.status.success(),
+        "update logs should fail without runs"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no update run includes forum"),
+        "unexpected stderr: {stderr}"
+    );
+}
+
+#[test]
+fn update_failure_notifies_webhook() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind listener");
+    let addr = listener.local_addr().expect("listener addr");
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            handle_http_request(stream, &tx);
+        }
+    });
+
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[notify]]\nurl = \"http://{addr}/hook\"\n\n[[discourse]]\nname = \"forum\"\nbaseurl = \"http://{addr}\"\napikey = \"key\"\napi_username = \"system\"\nssh_host = \"forum.invalid\"\n"
+        ),
+    );
+    // No ssh on PATH, so the update fails at the disk check.
+    let output = Command::new(env!("CARGO_BIN_EXE_dsc"))
+        .arg("-c")
+        .arg(&config_path)
+        .args(["update", "forum", "--no-changelog"])
+        .env("PATH", dir.path())
+        .env("DSC_UPDATE_STATE_DIR", dir.path().join("state"))
+        .output()
+        .expect("run update");
+    assert!(!output.status.success(), "update should fail without ssh");
+
+    let events: Vec<serde_json::Value> = rx
+        .try_iter()
+        .filter(|(request_line, _)| request_line.starts_with("POST /hook "))
+        .map(|(_, body)| serde_json::from_str(&body).expect("event json"))
+        .collect();
+    assert_eq!(events.len(), 2, "unexpected events: {events:?}");
+    assert_eq!(events[0]["event"], "update");
+    assert_eq!(events[0]["stage"], "started");
+    assert_eq!(events[0]["install"], "forum");
+    assert_eq!(events[1]["stage"], "failed");
+    assert!(
+        events[1]["error"]
+            .as_str()
+            .is_some_and(|error| !error.is_empty())
+    );
+}
+
+/// Record one request's line and body, then answer `200 {}`.
+fn handle_http_request(stream: TcpStream, tx: &mpsc::Sender<(String, String)>) -> Option<()> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).ok()?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().ok()?;
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).ok()?;
+    tx.send((
+        request_line.trim_end().to_string(),
+        String::from_utf8_lossy(&body).into_owned(),
+    ))
+    .ok()?;
+    let mut stream = reader.into_inner();
+    stream
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}")
+        .ok()
+}